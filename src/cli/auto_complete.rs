@@ -128,7 +128,7 @@ fn handle_bash(mut auto_complete_script: String) -> String {
         // The end index should include the esac word
         let end_index = end_index + 4;
         let curr_complete = &auto_complete_script[start_index..end_index].to_string();
-        let mut new_s = BASH_DATASTORES.to_string();
+        let mut new_s = BASH_DATASTORES.replace("__SATORI_TOOL__", &tool);
         if get_database_tools().contains(&tool) {
             new_s.push_str(BASH_DATABASES);
         }
@@ -165,10 +165,12 @@ fn handle_power_shell(auto_complete_script: &str) -> String {
         // Replace the current tool auto-complete with datastore auto-complete
         auto_complete_string.replace_range(
             start_index_cur_compl..end_index_cur_compl,
-            r"
-            $completionResults += Get-CompletionDatastores
+            &format!(
+                r"
+            $completionResults += Get-CompletionDatastores -ToolName '{tool}'
             $completionResults
-        ",
+        "
+            ),
         );
 
         // Return the original auto-complete to a new section, also add the database auto-complete if needed
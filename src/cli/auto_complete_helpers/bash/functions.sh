@@ -1,6 +1,11 @@
 
+# Lists the datastores the tool given as the first argument supports, or all of them
 function list_datastores() {
-    satori list --datastores | while IFS= read -r line; do
+    local tool_args=()
+    if [[ -n "$1" ]]; then
+        tool_args=(--tool "$1")
+    fi
+    satori list --datastores "${tool_args[@]}" | while IFS= read -r line; do
         echo "'$line'"
     done
 }
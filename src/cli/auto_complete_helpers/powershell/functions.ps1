@@ -1,6 +1,13 @@
 function Get-CompletionDatastores {
+    param (
+        [string]$ToolName
+    )
     $additionalResults = @()
-    $datastoreResults = Invoke-Expression 'satori list --datastores'
+    if ($ToolName) {
+        $datastoreResults = Invoke-Expression "satori list --datastores --tool $ToolName"
+    } else {
+        $datastoreResults = Invoke-Expression 'satori list --datastores'
+    }
     $datastoreResults -split '\r?\n' | ForEach-Object {
         $additionalResults += [CompletionResult]::new("'$_'", "'$_'", [CompletionResultType]::ParameterValue, "Datastore: $_")
     }
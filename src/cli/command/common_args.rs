@@ -25,3 +25,28 @@ pub fn get() -> Vec<Arg> {
             .default_value("false"),
     ]
 }
+
+/// Commands which run an interactive client can replace the satori process with it.
+pub fn exec() -> Arg {
+    Arg::new("exec")
+        .long("exec")
+        .help("Replace the satori process with the command, Unix only")
+        .action(ArgAction::SetTrue)
+}
+
+/// Commands which change files or run a command can print what they would do instead.
+pub fn dry_run() -> Vec<Arg> {
+    vec![
+        Arg::new("dry-run")
+            .long("dry-run")
+            .visible_alias("print-command")
+            .help("Print what would be run and changed, without doing it. The password is redacted")
+            .action(ArgAction::SetTrue),
+        Arg::new("plan-format")
+            .long("plan-format")
+            .value_parser(["text", "json"])
+            .default_value("text")
+            .requires("dry-run")
+            .help("The format of the dry run plan"),
+    ]
+}
@@ -0,0 +1,27 @@
+use clap::{arg, command, Arg, ArgAction, Command};
+
+use super::common_args;
+
+pub fn get_command() -> Command {
+    let mut args = vec![
+        arg!([datastore_name] "datastore name")
+            .required(true)
+            .help("The name as defined in Satori data portal")
+            .long_help("The name as defined in Satori data portal, use name#id or name@type when several datastores share the name"),
+        arg!([database] "Database name").help("Database name"),
+        arg!(--id <ID> "The id of the datastore, when several datastores share the name"),
+    ];
+    args.extend(common_args::get());
+    args.push(common_args::exec());
+    args.extend(common_args::dry_run());
+    args.push(
+        Arg::new("additional_args")
+            .help("Arguments passed to the tool")
+            .last(true)
+            .allow_hyphen_values(true)
+            .action(ArgAction::Append),
+    );
+    command!("connect")
+        .about("Connect to a datastore with the default tool of its type")
+        .args(args)
+}
@@ -0,0 +1,28 @@
+use clap::{arg, command, Arg, ArgAction, Command};
+
+use crate::env::EnvShell;
+
+use super::common_args;
+
+pub fn get_command() -> Command {
+    let mut args = vec![
+        arg!([datastore_name] "datastore name")
+            .required(true)
+            .help("The name as defined in Satori data portal")
+            .long_help("The name as defined in Satori data portal, use name#id or name@type when several datastores share the name"),
+        arg!([database] "database name").help("Database name"),
+        arg!(--id <ID> "The id of the datastore, when several datastores share the name"),
+        Arg::new("shell")
+            .long("shell")
+            .value_parser(EnvShell::NAMES)
+            .help("The syntax of the output, defaults to the current shell"),
+        Arg::new("unset")
+            .long("unset")
+            .help("Print the commands which remove the variables")
+            .action(ArgAction::SetTrue),
+    ];
+    args.extend(common_args::get());
+    command!("env")
+        .about("Print the environment variables to connect to the datastore")
+        .args(args)
+}
@@ -0,0 +1,28 @@
+use clap::{arg, command, Arg, ArgAction, Command};
+
+use super::common_args;
+
+pub fn get_command() -> Command {
+    let mut args = vec![
+        arg!([datastore_name] "datastore name")
+            .required(true)
+            .help("The name as defined in Satori data portal")
+            .long_help("The name as defined in Satori data portal, use name#id or name@type when several datastores share the name"),
+        arg!(-d --database <DATABASE> "Database name"),
+        arg!(--id <ID> "The id of the datastore, when several datastores share the name"),
+    ];
+    args.extend(common_args::get());
+    args.push(common_args::exec());
+    args.extend(common_args::dry_run());
+    args.push(
+        Arg::new("command")
+            .help("The command to run, with its arguments")
+            .required(true)
+            .last(true)
+            .allow_hyphen_values(true)
+            .action(ArgAction::Append),
+    );
+    command!("exec")
+        .about("Run a command with the datastore connection details in its environment")
+        .args(args)
+}
@@ -6,6 +6,8 @@ pub fn get_command() -> Command {
         .hide(true)
         .args(vec![
             arg!(--datastores "Get all available datastores"),
+            arg!(--tool <tool> "Only the datastores the tool supports").requires("datastores"),
+            arg!(--all "Get all datastores, including the ones which can't be used"),
             arg!(--databases <datastore_name> "List of databases for the datastore"),
         ])
 }
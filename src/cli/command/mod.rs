@@ -2,11 +2,16 @@ use clap::{command, Command};
 
 mod auto_complete;
 mod common_args;
+mod connect;
+mod env;
+mod exec;
 mod list;
 pub mod login;
 mod pwd;
 mod run;
+mod status;
 mod tools;
+mod url;
 
 pub fn get() -> Command {
     let mut main_command = command!("satori")
@@ -17,6 +22,11 @@ pub fn get() -> Command {
         .hide(true)
         .subcommand(list::get_command())
         .hide(true)
+        .subcommand(status::get_command())
+        .subcommand(url::get_command())
+        .subcommand(env::get_command())
+        .subcommand(exec::get_command())
+        .subcommand(connect::get_command())
         .arg_required_else_help(true);
     for command in tools::get_commands() {
         main_command = main_command.subcommand(command);
@@ -1,10 +1,13 @@
 use std::path::PathBuf;
 
-use clap::{arg, value_parser, Arg, ArgAction, Command};
+use clap::{arg, builder::PossibleValuesParser, value_parser, Arg, ArgAction, Command};
 
 use crate::{
     cli::auto_complete,
-    helpers::{self, tools::CliArgs},
+    helpers::{
+        self,
+        tools::{self, CliArgType, CliArgs},
+    },
 };
 
 use super::common_args;
@@ -26,20 +29,25 @@ pub fn get_command() -> Command {
         args.push(
             arg!( [datastore_name] "datastore name")
                 .required(true)
-                .help("The name as defined in Satori data portal"),
+                .help("The name as defined in Satori data portal")
+                .long_help("The name as defined in Satori data portal, use name#id or name@type when several datastores share the name"),
+        );
+        args.push(
+            arg!(--id <ID> "The id of the datastore, when several datastores share the name"),
         );
 
         for tool_arg in command_args {
-            let name = string_to_static_str(tool_arg.name);
-            let help = string_to_static_str(tool_arg.help);
-            let arg = Arg::new(name).help(help).value_name(name);
-            let arg = if tool_arg.required {
-                arg.required(true)
-            } else {
-                arg
-            };
-            args.push(arg);
+            if tools::RESERVED_ARG_NAMES.contains(&tool_arg.name.as_str()) {
+                eprintln!(
+                    "Skipping argument {} of tool {command_name}, it conflicts with a built-in argument",
+                    tool_arg.name
+                );
+                continue;
+            }
+            args.push(get_tool_arg(tool_arg));
         }
+        args.push(common_args::exec());
+        args.extend(common_args::dry_run());
         args.push(additional_args());
         let command = command.args(args);
         run_command = run_command.subcommand(command);
@@ -48,12 +56,65 @@ pub fn get_command() -> Command {
     run_command
 }
 
-/// Loads the tools.yaml file.
+fn get_tool_arg(tool_arg: CliArgs) -> Arg {
+    let name = string_to_static_str(tool_arg.name);
+    let help = string_to_static_str(tool_arg.help);
+    let arg = Arg::new(name).help(help).required(tool_arg.required);
+    let arg = match tool_arg.r#type {
+        CliArgType::Positional => arg.value_name(name),
+        CliArgType::Option => arg
+            .long(name)
+            .value_name(string_to_static_str(name.to_uppercase()))
+            .action(ArgAction::Set),
+        CliArgType::Flag => arg.long(name).action(ArgAction::SetTrue),
+    };
+    let arg = match tool_arg.short {
+        Some(short) if tool_arg.r#type != CliArgType::Positional => arg.short(short),
+        _ => arg,
+    };
+    let arg = match tool_arg.default {
+        Some(default) if tool_arg.r#type != CliArgType::Flag => {
+            arg.default_value(string_to_static_str(default))
+        }
+        _ => arg,
+    };
+    if tool_arg.choices.is_empty() || tool_arg.r#type == CliArgType::Flag {
+        arg
+    } else {
+        let choices = tool_arg
+            .choices
+            .into_iter()
+            .map(string_to_static_str)
+            .collect::<Vec<&'static str>>();
+        arg.value_parser(PossibleValuesParser::new(choices))
+    }
+}
+
+/// Loads the built-in and the user defined tools.
 fn from_file() -> Vec<(&'static str, Vec<CliArgs>)> {
-    let tools = helpers::tools::get_or_init();
+    let tools = match helpers::tools::get_or_init() {
+        Ok(tools) => tools,
+        Err(err) => {
+            eprintln!("{err}");
+            return vec![];
+        }
+    };
+    let static_commands = get_static_commands();
     tools
         .value
         .iter()
+        .filter(|tool| {
+            let is_static = static_commands
+                .iter()
+                .any(|command| command.get_name() == tool.name);
+            if is_static {
+                eprintln!(
+                    "Skipping tool {}, it conflicts with a built-in command",
+                    tool.name
+                );
+            }
+            !is_static
+        })
         .map(|tool| {
             auto_complete::add_tool_autocomplete(tool.name.clone());
             for cli_arg in &tool.cli_args {
@@ -79,12 +140,21 @@ fn get_dbt_command() -> Command {
     let mut args = vec![
         Arg::new("profile-dir")
             .long("profile-dir")
+            .alias("profiles-dir")
             .required(false)
             .value_parser(value_parser!(PathBuf))
             .help("The path to the dbt profiles directory"),
+        Arg::new("project-dir")
+            .long("project-dir")
+            .required(false)
+            .value_parser(value_parser!(PathBuf))
+            .help("The path to the dbt project directory"),
+        arg!(--profile <PROFILE> "DBT profile, overrides the profile of the project")
+            .required(false),
         arg!(--target <PROFILE> "DBT target").required(false),
     ];
     args.extend(common_args::get());
+    args.extend(common_args::dry_run());
     args.push(additional_args());
 
     Command::new("dbt").about("dbt").args(args)
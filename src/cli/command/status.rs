@@ -0,0 +1,5 @@
+use clap::{command, Command};
+
+pub fn get_command() -> Command {
+    command!("status").about("Show the state of the local Satori cache")
+}
@@ -1,15 +1,25 @@
 use std::path::PathBuf;
 
-use clap::{value_parser, Arg, Command};
+use clap::{arg, builder::PossibleValuesParser, value_parser, Arg, ArgAction, Command};
 
 use super::common_args;
 
+/// The postgres dialect datastore types, which can be added to the pgpass and service files.
+const PGPASS_TYPES: [&str; 4] = ["postgresql", "redshift", "greenplum", "cockroachdb"];
+
 pub fn get_commands() -> Vec<Command> {
-    vec![get_command_pgpass(), get_command_aws()]
+    vec![
+        get_command_pgpass(),
+        get_command_pgservice(),
+        get_command_aws(),
+        get_command_dbt(),
+        get_command_tools(),
+    ]
 }
 
 fn get_command_pgpass() -> Command {
     let mut args = common_args::get();
+    args.extend(common_args::dry_run());
     args.push(
         Arg::new("path")
             .short('p')
@@ -18,6 +28,13 @@ fn get_command_pgpass() -> Command {
             .help("Path to the pgpass file should include the filename, for example /foo/pgpass.config, default will be used based on the OS")
             .value_parser(value_parser!(PathBuf)),
     );
+    args.extend(get_datastore_filter_args());
+    args.push(
+        Arg::new("wildcard-databases")
+            .long("wildcard-databases")
+            .action(ArgAction::SetTrue)
+            .help("Add a single entry matching all the databases of each datastore"),
+    );
 
     // arg!(-p --path <VALUE> "Path to the pgpass file, default will be used based on the OS")
     // );
@@ -26,8 +43,125 @@ fn get_command_pgpass() -> Command {
         .args(args)
 }
 
+fn get_command_pgservice() -> Command {
+    let mut args = common_args::get();
+    args.extend(common_args::dry_run());
+    args.push(
+        Arg::new("path")
+            .short('p')
+            .long("path")
+            .required(false)
+            .help("Path to the connection service file, defaults to PGSERVICEFILE or ~/.pg_service.conf")
+            .value_parser(value_parser!(PathBuf)),
+    );
+    args.extend(get_datastore_filter_args());
+    Command::new("pgservice")
+        .about("Adds the Satori datastores to the connection service file, to connect with service=<name>")
+        .args(args)
+}
+
+/// The arguments selecting the postgres dialect datastores.
+fn get_datastore_filter_args() -> Vec<Arg> {
+    vec![
+        Arg::new("datastore")
+            .long("datastore")
+            .required(false)
+            .action(ArgAction::Append)
+            .help("Only add the datastores with this name, can be a glob pattern like 'prod-*'"),
+        Arg::new("type")
+            .long("type")
+            .required(false)
+            .action(ArgAction::Append)
+            .value_parser(PossibleValuesParser::new(PGPASS_TYPES))
+            .help("Only add the datastores of this type"),
+    ]
+}
+
 fn get_command_aws() -> Command {
+    let mut credential_process_args = vec![Arg::new("datastore")
+        .required(true)
+        .help("The Satori S3 or Athena datastore name")];
+    credential_process_args.extend(common_args::get());
     Command::new("aws")
         .about("Creates a aws profile to be used with s3")
         .args(common_args::get())
+        .args(common_args::dry_run())
+        .arg(
+            Arg::new("credential-process")
+                .long("credential-process")
+                .action(ArgAction::SetTrue)
+                .help("Write a credential_process running satori to the AWS config instead of writing the credentials"),
+        )
+        .subcommand(
+            Command::new("credential-process")
+                .about("Prints the Satori credentials in the format of the AWS credential_process")
+                .args(credential_process_args),
+        )
+}
+
+fn get_command_dbt() -> Command {
+    let mut args = vec![
+        Arg::new("datastore")
+            .required(true)
+            .help("The Satori datastore name"),
+        arg!(--database <DATABASE> "The database of the target").required(true),
+        arg!(--schema <SCHEMA> "The schema of the target").required(true),
+        arg!(--target <TARGET> "The name of the target")
+            .required(false)
+            .default_value("dev"),
+        arg!(--profile <PROFILE> "DBT profile, defaults to the profile of the project")
+            .required(false),
+        Arg::new("project-dir")
+            .long("project-dir")
+            .required(false)
+            .value_parser(value_parser!(PathBuf))
+            .help("The path to the dbt project directory"),
+        Arg::new("profile-dir")
+            .long("profile-dir")
+            .alias("profiles-dir")
+            .required(false)
+            .value_parser(value_parser!(PathBuf))
+            .help("The path to the dbt profiles directory"),
+        Arg::new("s3-staging-dir")
+            .long("s3-staging-dir")
+            .required(false)
+            .help("The s3_staging_dir of Athena targets"),
+        Arg::new("region")
+            .long("region")
+            .required(false)
+            .help("The region_name of Athena targets"),
+        Arg::new("force")
+            .long("force")
+            .help("Replace the target if the profile already has it")
+            .action(ArgAction::SetTrue),
+    ];
+    args.extend(common_args::get());
+    args.extend(common_args::dry_run());
+
+    Command::new("dbt")
+        .about("Manage the dbt profiles of Satori datastores")
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("init")
+                .about("Adds a target of a Satori datastore to a dbt profile")
+                .args(args),
+        )
+}
+
+fn get_command_tools() -> Command {
+    Command::new("tools")
+        .about("Manage the tools definitions used by the run command")
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("validate")
+                .about(
+                    "Validates the tools definitions, the built-in and the user tools by default",
+                )
+                .arg(
+                    Arg::new("file")
+                        .required(false)
+                        .help("Validate only this tools file")
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
 }
@@ -0,0 +1,28 @@
+use clap::{arg, command, Arg, ArgAction, Command};
+
+use crate::helpers::connection_url::UrlFormat;
+
+use super::common_args;
+
+pub fn get_command() -> Command {
+    let mut args = vec![
+        arg!([datastore_name] "datastore name")
+            .required(true)
+            .help("The name as defined in Satori data portal")
+            .long_help("The name as defined in Satori data portal, use name#id or name@type when several datastores share the name"),
+        arg!([database] "database name").help("Database name"),
+        arg!(--id <ID> "The id of the datastore, when several datastores share the name"),
+        Arg::new("format")
+            .long("format")
+            .value_parser(UrlFormat::NAMES)
+            .help("The format of the url, defaults to the common format of the datastore type"),
+        Arg::new("redact")
+            .long("redact")
+            .help("Replace the password with ****, for sharing the url")
+            .action(ArgAction::SetTrue),
+    ];
+    args.extend(common_args::get());
+    command!("url")
+        .about("Print a connection url for the datastore")
+        .args(args)
+}
@@ -2,14 +2,29 @@ use std::path::PathBuf;
 
 use clap_complete::Shell;
 
-use crate::{list::data::List, login::Login, pwd::Pwd, run::Run, tools::Tools};
+use crate::{
+    env::Env,
+    helpers::dry_run::DryRun,
+    list::data::List,
+    login::Login,
+    pwd::Pwd,
+    run::{Executer, Run},
+    status::Status,
+    tools::Tools,
+    url::Url,
+};
 
 #[derive(Debug)]
 pub enum Flow {
     Login(Login),
-    Run(Run),
+    Run(Run, Executer),
     AutoComplete(Shell, PathBuf),
     List(List),
     Tools(Tools),
     Pwd(Pwd),
+    Status(Status),
+    Url(Url),
+    Env(Env),
+    /// Runs the flow, then prints the plan it recorded.
+    DryRun(Box<Flow>, DryRun),
 }
@@ -1,6 +1,14 @@
 use clap::ArgMatches;
 
-use crate::{helpers::logger::DEBUG, login::LoginBuilder};
+use crate::{
+    cli::Flow,
+    helpers::{
+        dry_run::{DryRun, PlanFormat},
+        logger::{DEBUG, LOG_TO_STDERR},
+    },
+    login::LoginBuilder,
+    run::{CommandExecuter, Executer},
+};
 
 pub fn set_debug(args: &ArgMatches) {
     DEBUG.set(args.get_flag("debug")).unwrap();
@@ -30,3 +38,36 @@ pub(super) fn build_login_common_args(args: &ArgMatches) -> LoginBuilder {
         login_builder
     }
 }
+
+/// The command is only recorded on a dry run.
+pub(super) fn build_executer(args: &ArgMatches, dry_run: &Option<DryRun>) -> Executer {
+    match dry_run {
+        Some(dry_run) => Executer::DryRun(dry_run.clone()),
+        None => Executer::Command(CommandExecuter {
+            replace_process: args.try_get_one::<bool>("exec").ok().flatten() == Some(&true),
+        }),
+    }
+}
+
+pub(super) fn build_dry_run(args: &ArgMatches) -> Option<DryRun> {
+    if !args.get_flag("dry-run") {
+        return None;
+    }
+    let format = match args.get_one::<String>("plan-format").map(String::as_str) {
+        Some("json") => {
+            // Keep the stdout a valid json document
+            LOG_TO_STDERR.set(true).unwrap();
+            PlanFormat::Json
+        }
+        _ => PlanFormat::Text,
+    };
+    Some(DryRun::new(format))
+}
+
+/// The plan is printed once the flow is done.
+pub(super) fn with_dry_run(flow: Flow, dry_run: Option<DryRun>) -> Flow {
+    match dry_run {
+        Some(dry_run) => Flow::DryRun(Box::new(flow), dry_run),
+        None => flow,
+    }
+}
@@ -0,0 +1,37 @@
+use clap::ArgMatches;
+
+use crate::{
+    cli::{
+        parsers::{
+            common::{self, build_login_common_args},
+            run,
+        },
+        Flow,
+    },
+    run::{Connect, Run},
+};
+
+pub fn build(args: &ArgMatches) -> Flow {
+    common::set_debug(args);
+    let login_builder = build_login_common_args(args);
+    let login = if args.get_flag("no-persist") {
+        login_builder.write_to_file(false)
+    } else {
+        login_builder
+    }
+    .build()
+    .unwrap();
+
+    let dry_run = common::build_dry_run(args);
+    let flow = Flow::Run(
+        Run::Connect(Connect {
+            login,
+            datastore_name: args.get_one::<String>("datastore_name").unwrap().to_owned(),
+            datastore_id: args.get_one::<String>("id").cloned(),
+            database: args.get_one::<String>("database").cloned(),
+            additional_args: run::get_additional_args(args),
+        }),
+        common::build_executer(args, &dry_run),
+    );
+    common::with_dry_run(flow, dry_run)
+}
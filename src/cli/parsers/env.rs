@@ -0,0 +1,54 @@
+use clap::ArgMatches;
+
+use crate::{
+    cli::{
+        parsers::common::{self, build_login_common_args},
+        Flow,
+    },
+    env::{Env, EnvShell},
+    helpers::logger::LOG_TO_STDERR,
+};
+
+pub fn build(args: &ArgMatches) -> Flow {
+    common::set_debug(args);
+    // The output is evaluated by the shell, keep it the only thing on stdout
+    LOG_TO_STDERR.set(true).unwrap();
+    let login_builder = build_login_common_args(args);
+    let login = if args.get_flag("no-persist") {
+        login_builder.write_to_file(false)
+    } else {
+        login_builder
+    }
+    .build()
+    .unwrap();
+    let datastore_name = args.get_one::<String>("datastore_name").unwrap().to_owned();
+    let shell = match args.get_one::<String>("shell") {
+        Some(shell) => shell.parse::<EnvShell>().unwrap(),
+        None => get_current_shell(),
+    };
+    Flow::Env(Env {
+        login,
+        datastore_name,
+        datastore_id: args.get_one::<String>("id").cloned(),
+        database: args.get_one::<String>("database").cloned(),
+        shell,
+        unset: args.get_flag("unset"),
+    })
+}
+
+#[cfg(target_family = "windows")]
+fn get_current_shell() -> EnvShell {
+    EnvShell::PowerShell
+}
+
+#[cfg(not(target_family = "windows"))]
+fn get_current_shell() -> EnvShell {
+    let shell = std::env::var("SHELL").unwrap_or_default();
+    if shell.ends_with("/fish") {
+        EnvShell::Fish
+    } else if shell.ends_with("/zsh") {
+        EnvShell::Zsh
+    } else {
+        EnvShell::Bash
+    }
+}
@@ -0,0 +1,42 @@
+use clap::ArgMatches;
+
+use crate::{
+    cli::{
+        parsers::common::{self, build_login_common_args},
+        Flow,
+    },
+    run::{Exec, Run},
+};
+
+pub fn build(args: &ArgMatches) -> Flow {
+    common::set_debug(args);
+    let login_builder = build_login_common_args(args);
+    let login = if args.get_flag("no-persist") {
+        login_builder.write_to_file(false)
+    } else {
+        login_builder
+    }
+    .build()
+    .unwrap();
+    let datastore_name = args.get_one::<String>("datastore_name").unwrap().to_owned();
+    let mut command = args
+        .get_many::<String>("command")
+        .unwrap()
+        .cloned()
+        .collect::<Vec<String>>();
+    let command_args = command.split_off(1);
+
+    let dry_run = common::build_dry_run(args);
+    let flow = Flow::Run(
+        Run::Exec(Exec {
+            login,
+            datastore_name,
+            datastore_id: args.get_one::<String>("id").cloned(),
+            database: args.get_one::<String>("database").cloned(),
+            command: command.remove(0),
+            args: command_args,
+        }),
+        common::build_executer(args, &dry_run),
+    );
+    common::with_dry_run(flow, dry_run)
+}
@@ -8,8 +8,10 @@ use crate::{
 
 pub fn build(args: &ArgMatches) -> Result<Flow, CliError> {
     let satori_folder_path = helpers::default_app_folder::get()?;
-    let resource_type = if args.get_flag("datastores") {
-        ResourceType::Datastores
+    let resource_type = if args.get_flag("all") {
+        ResourceType::AllDatastores
+    } else if args.get_flag("datastores") {
+        ResourceType::Datastores(args.get_one::<String>("tool").cloned())
     } else {
         let database = args.get_one::<String>("databases").unwrap();
         ResourceType::Databases(database.to_owned())
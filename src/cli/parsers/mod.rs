@@ -4,11 +4,16 @@ use super::{CliError, Flow};
 
 mod auto_complete;
 mod common;
+mod connect;
+mod env;
+mod exec;
 mod list;
 mod login;
 mod pwd;
 mod run;
+mod status;
 mod tools;
+mod url;
 
 pub fn parse(command: Command) -> Result<Flow, CliError> {
     let matches = command.get_matches();
@@ -18,8 +23,15 @@ pub fn parse(command: Command) -> Result<Flow, CliError> {
         "run" => run::build(command_args),
         "list" => list::build(command_args),
         "auto_complete" => Ok(auto_complete::build(command_args)),
-        "pgpass" | "aws" => tools::build(command_name, command_args),
+        "pgpass" | "pgservice" | "aws" | "dbt" | "tools" => {
+            tools::build(command_name, command_args)
+        }
         "pwd" => Ok(pwd::build(command_args)),
+        "status" => status::build(),
+        "url" => Ok(url::build(command_args)),
+        "env" => Ok(env::build(command_args)),
+        "exec" => Ok(exec::build(command_args)),
+        "connect" => Ok(connect::build(command_args)),
         _ => panic!("No subcommand found"),
     }
 }
@@ -1,6 +1,5 @@
 use std::{
-    env,
-    fs::{self, File},
+    env, fs,
     path::{Path, PathBuf},
 };
 
@@ -19,18 +18,24 @@ pub fn build(args: &ArgMatches) -> Result<Flow, CliError> {
         .build()
         .unwrap();
     let profiles_path = get_profiles_path(args);
-
-    let profile_name = get_profile()?;
-
+    let project_dir = get_project_dir(args);
+    let profile_name = args.get_one::<String>("profile").cloned();
     let target = get_target(args);
     let additional_args = common::get_additional_args(args);
-    Ok(Flow::Run(Run::Dbt(Dbt {
-        login,
-        target,
-        profile_name,
-        profiles_path,
-        additional_args,
-    })))
+    let dry_run = parsers::common::build_dry_run(args);
+    let flow = Flow::Run(
+        Run::Dbt(Dbt {
+            login,
+            target,
+            profile_name,
+            project_dir,
+            profiles_path,
+            additional_args,
+            dry_run: dry_run.clone(),
+        }),
+        parsers::common::build_executer(args, &dry_run),
+    );
+    Ok(parsers::common::with_dry_run(flow, dry_run))
 }
 
 /// DBT select the profiles directory as follow:
@@ -39,14 +44,14 @@ pub fn build(args: &ArgMatches) -> Result<Flow, CliError> {
 /// 3. profiles.yml file is found in the current directory
 /// 4. default to ~/.dbt directory
 ///    The file is always named profiles.yml
-fn get_profiles_path(args: &ArgMatches) -> PathBuf {
+pub fn get_profiles_path(args: &ArgMatches) -> PathBuf {
     match args.get_one::<PathBuf>("profile-dir") {
         Some(profile_dir) => Path::new(&profile_dir).to_path_buf(),
         None => match env::var("DBT_PROFILES_DIR") {
             Ok(profile_dir) => Path::new(&profile_dir).to_path_buf(),
             Err(_) => {
                 if fs::metadata("profiles.yml").is_ok() {
-                    println!("profiles.yml found in current directory");
+                    log::debug!("profiles.yml found in current directory");
                     env::current_dir().unwrap()
                 } else {
                     homedir::get_my_home()
@@ -60,20 +65,18 @@ fn get_profiles_path(args: &ArgMatches) -> PathBuf {
     .join("profiles.yml")
 }
 
-fn get_profile() -> Result<String, CliError> {
-    let file = File::open("dbt_project.yml").map_err(CliError::DbtProjectFileError)?;
-    let reader = std::io::BufReader::new(file);
-    let dbt_project =
-        serde_yaml::from_reader::<_, DbtProject>(reader).map_err(CliError::DbtProjectParseError)?;
-    Ok(dbt_project.profile)
+/// `--project-dir`, or the `DBT_PROJECT_DIR` environment variable. When neither is set the
+/// project is searched from the current directory up.
+pub fn get_project_dir(args: &ArgMatches) -> Option<PathBuf> {
+    args.get_one::<PathBuf>("project-dir")
+        .cloned()
+        .or_else(|| env::var_os("DBT_PROJECT_DIR").map(PathBuf::from))
 }
 
-/// If no target specified, will use the default target
+/// `--target`, or the `DBT_TARGET` environment variable. If neither is set, will use the
+/// default target of the profile.
 fn get_target(args: &ArgMatches) -> Option<String> {
-    args.get_one("target").cloned()
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct DbtProject {
-    profile: String,
+    args.get_one("target")
+        .cloned()
+        .or_else(|| env::var("DBT_TARGET").ok())
 }
@@ -1,15 +1,17 @@
+use std::collections::BTreeMap;
+
 use clap::ArgMatches;
 
 use crate::{
     cli::{
         parsers::{self, common::build_login_common_args, run::common},
-        Flow,
+        CliError, Flow,
     },
-    helpers::tools::{self, Tool},
-    run::{DynamicTool, Run},
+    helpers::tools::{self, CliArgType, Tool},
+    run::{DynamicTool, Run, ToolArg},
 };
 
-pub fn build(tool_name: &str, args: &ArgMatches) -> Flow {
+pub fn build(tool_name: &str, args: &ArgMatches) -> Result<Flow, CliError> {
     parsers::common::set_debug(args);
     let login_builder = build_login_common_args(args);
     let login = if args.get_flag("no-persist") {
@@ -20,25 +22,28 @@ pub fn build(tool_name: &str, args: &ArgMatches) -> Flow {
     .build()
     .unwrap();
     let datastore_name = args.get_one::<String>("datastore_name").unwrap().to_owned();
+    let datastore_id = args.get_one::<String>("id").cloned();
 
     let additional_args = common::get_additional_args(args);
 
-    let tools_data = tools::get_or_init();
-    for tool_data in &tools_data.value {
-        if tool_name == tool_data.name {
-            let database = get_database(args, tool_data);
-            let dynamic_tool = DynamicTool {
-                tool: tool_name.to_owned(),
-                login,
-                datastore_name,
-                additional_args,
-                database,
-            };
-            let connect = Run::DynamicTool(dynamic_tool);
-            return Flow::Run(connect);
-        }
-    }
-    panic!("Tool name wasn't found");
+    let tool_data = tools::get_tool(tool_name)?;
+    let database = get_database(args, tool_data);
+    let tool_args = get_tool_args(args, tool_data);
+    let dynamic_tool = DynamicTool {
+        tool: tool_name.to_owned(),
+        login,
+        datastore_name,
+        datastore_id,
+        additional_args,
+        database,
+        args: tool_args,
+    };
+    let dry_run = parsers::common::build_dry_run(args);
+    let flow = Flow::Run(
+        Run::DynamicTool(dynamic_tool),
+        parsers::common::build_executer(args, &dry_run),
+    );
+    Ok(parsers::common::with_dry_run(flow, dry_run))
 }
 
 fn get_database(args: &ArgMatches, tool_data: &Tool) -> Option<String> {
@@ -49,3 +54,21 @@ fn get_database(args: &ArgMatches, tool_data: &Tool) -> Option<String> {
     }
     None
 }
+
+/// The values of the tool `cli_args`, args which weren't given and have no default are left out.
+fn get_tool_args(args: &ArgMatches, tool_data: &Tool) -> BTreeMap<String, ToolArg> {
+    tool_data
+        .cli_args
+        .iter()
+        .filter(|cli_arg| !tools::RESERVED_ARG_NAMES.contains(&cli_arg.name.as_str()))
+        .filter_map(|cli_arg| {
+            let value = match cli_arg.r#type {
+                CliArgType::Flag => ToolArg::Flag(args.get_flag(&cli_arg.name)),
+                CliArgType::Option | CliArgType::Positional => {
+                    ToolArg::Value(args.get_one::<String>(&cli_arg.name)?.clone())
+                }
+            };
+            Some((cli_arg.context_name(), value))
+        })
+        .collect()
+}
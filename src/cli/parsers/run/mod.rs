@@ -5,11 +5,14 @@ mod dynamic_tools;
 use crate::cli::{CliError, Flow};
 use clap::ArgMatches;
 
+pub use common::get_additional_args;
+pub use dbt::{get_profiles_path, get_project_dir};
+
 pub fn build(args: &ArgMatches) -> Result<Flow, CliError> {
     let (tool_name, tool_args) = args.subcommand().unwrap();
     if tool_name == "dbt" {
         dbt::build(tool_args)
     } else {
-        Ok(dynamic_tools::build(tool_name, tool_args))
+        dynamic_tools::build(tool_name, tool_args)
     }
 }
@@ -0,0 +1,11 @@
+use satori_cli::helpers;
+
+use crate::{
+    cli::{CliError, Flow},
+    status::Status,
+};
+
+pub fn build() -> Result<Flow, CliError> {
+    let satori_folder_path = helpers::default_app_folder::get()?;
+    Ok(Flow::Status(Status { satori_folder_path }))
+}
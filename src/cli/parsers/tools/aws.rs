@@ -7,7 +7,8 @@ use clap::ArgMatches;
 
 use crate::{
     cli::{self, parsers::common},
-    tools::aws::Aws,
+    helpers::logger::LOG_TO_STDERR,
+    tools::aws::{Aws, AwsCredentialProcess},
 };
 
 const ENV_CREDENTIALS_FILE_PATH: &str = "AWS_SHARED_CREDENTIALS_FILE";
@@ -25,9 +26,22 @@ pub fn build(args: &ArgMatches) -> Result<Aws, cli::errors::CliError> {
         login,
         credentials_path,
         config_path,
+        credential_process: args.get_flag("credential-process"),
+        dry_run: common::build_dry_run(args),
     })
 }
 
+pub fn build_credential_process(args: &ArgMatches) -> AwsCredentialProcess {
+    common::set_debug(args);
+    // The output is read by the AWS CLI and SDKs, keep it the only thing on stdout
+    LOG_TO_STDERR.set(true).unwrap();
+    let login = common::build_login_common_args(args).build().unwrap();
+    AwsCredentialProcess {
+        login,
+        datastore_name: args.get_one::<String>("datastore").cloned().unwrap(),
+    }
+}
+
 fn get_credentials_path() -> Result<PathBuf, cli::errors::CliError> {
     get_from_env_or_default(ENV_CREDENTIALS_FILE_PATH, AWS_CREDENTIALS_FILE)
 }
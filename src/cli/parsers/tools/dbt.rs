@@ -0,0 +1,30 @@
+use clap::ArgMatches;
+
+use crate::{
+    cli::{
+        parsers::{common, run},
+        CliError,
+    },
+    tools::dbt::DbtInit,
+};
+
+pub fn build(args: &ArgMatches) -> Result<DbtInit, CliError> {
+    let (_, init_args) = args.subcommand().unwrap();
+    common::set_debug(init_args);
+    let login = common::build_login_common_args(init_args).build().unwrap();
+    let get_string = |name: &str| init_args.get_one::<String>(name).cloned();
+    Ok(DbtInit {
+        login,
+        datastore_name: get_string("datastore").unwrap(),
+        database: get_string("database").unwrap(),
+        schema: get_string("schema").unwrap(),
+        target: get_string("target").unwrap(),
+        profile_name: get_string("profile"),
+        project_dir: run::get_project_dir(init_args),
+        profiles_path: run::get_profiles_path(init_args),
+        force: init_args.get_flag("force"),
+        s3_staging_dir: get_string("s3-staging-dir"),
+        region: get_string("region"),
+        dry_run: common::build_dry_run(init_args),
+    })
+}
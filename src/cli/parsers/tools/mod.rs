@@ -1,17 +1,53 @@
 use clap::ArgMatches;
 
 use crate::{
-    cli::{errors, Flow},
+    cli::{errors, parsers::common, Flow},
     tools::Tools,
 };
 
 mod aws;
+mod dbt;
 mod pgpass;
+mod pgservice;
+mod validate;
 
 pub fn build(tool_name: &str, args: &ArgMatches) -> Result<Flow, errors::CliError> {
     match tool_name {
-        "pgpass" => Ok(Flow::Tools(Tools::PgPass(pgpass::build(args)?))),
-        "aws" => Ok(Flow::Tools(Tools::Aws(aws::build(args)?))),
+        "pgpass" => {
+            let pgpass = pgpass::build(args)?;
+            let dry_run = pgpass.dry_run.clone();
+            Ok(common::with_dry_run(
+                Flow::Tools(Tools::PgPass(pgpass)),
+                dry_run,
+            ))
+        }
+        "pgservice" => {
+            let pgservice = pgservice::build(args)?;
+            let dry_run = pgservice.dry_run.clone();
+            Ok(common::with_dry_run(
+                Flow::Tools(Tools::PgService(pgservice)),
+                dry_run,
+            ))
+        }
+        "aws" => {
+            if let Some(("credential-process", credential_process_args)) = args.subcommand() {
+                return Ok(Flow::Tools(Tools::AwsCredentialProcess(
+                    aws::build_credential_process(credential_process_args),
+                )));
+            }
+            let aws = aws::build(args)?;
+            let dry_run = aws.dry_run.clone();
+            Ok(common::with_dry_run(Flow::Tools(Tools::Aws(aws)), dry_run))
+        }
+        "dbt" => {
+            let dbt_init = dbt::build(args)?;
+            let dry_run = dbt_init.dry_run.clone();
+            Ok(common::with_dry_run(
+                Flow::Tools(Tools::DbtInit(dbt_init)),
+                dry_run,
+            ))
+        }
+        "tools" => Ok(Flow::Tools(Tools::Validate(validate::build(args)))),
         _ => panic!("No tool found"),
     }
 }
@@ -7,6 +7,7 @@ use crate::{
         parsers::common::{self, build_login_common_args},
         CliError,
     },
+    helpers::satori_console::DatastoreType,
     tools::pgpass::PgPass,
 };
 
@@ -26,9 +27,38 @@ pub fn build(args: &ArgMatches) -> Result<PgPass, CliError> {
     Ok(PgPass {
         login,
         path: pgpass_path,
+        datastores: get_datastores(args),
+        types: get_types(args),
+        wildcard_databases: args.get_flag("wildcard-databases"),
+        dry_run: common::build_dry_run(args),
     })
 }
 
+/// The `--datastore` names and patterns.
+pub(super) fn get_datastores(args: &ArgMatches) -> Vec<String> {
+    args.get_many::<String>("datastore")
+        .unwrap_or_default()
+        .cloned()
+        .collect()
+}
+
+pub(super) fn get_types(args: &ArgMatches) -> Vec<DatastoreType> {
+    args.get_many::<String>("type")
+        .unwrap_or_default()
+        .map(|datastore_type| get_datastore_type(datastore_type))
+        .collect()
+}
+
+/// One of the values of the `--type` argument.
+fn get_datastore_type(datastore_type: &str) -> DatastoreType {
+    match datastore_type {
+        "redshift" => DatastoreType::Redshift,
+        "greenplum" => DatastoreType::Greenplum,
+        "cockroachdb" => DatastoreType::CockroachDb,
+        _ => DatastoreType::Postgresql,
+    }
+}
+
 #[cfg(target_family = "unix")]
 fn get_pgpass_file_path() -> Result<PathBuf, CliError> {
     Ok(homedir::get_my_home()?
@@ -0,0 +1,43 @@
+use std::{env, path::PathBuf};
+
+use clap::ArgMatches;
+
+use crate::{
+    cli::{
+        parsers::common::{self, build_login_common_args},
+        CliError,
+    },
+    tools::pgservice::PgService,
+};
+
+use super::pgpass;
+
+const ENV_SERVICE_FILE_PATH: &str = "PGSERVICEFILE";
+const SERVICE_FILE_NAME: &str = ".pg_service.conf";
+
+pub fn build(args: &ArgMatches) -> Result<PgService, CliError> {
+    common::set_debug(args);
+    let login = build_login_common_args(args).build().unwrap();
+    let path = match args.get_one::<PathBuf>("path").cloned() {
+        Some(path) => path,
+        None => get_service_file_path()?,
+    };
+
+    Ok(PgService {
+        login,
+        path,
+        datastores: pgpass::get_datastores(args),
+        types: pgpass::get_types(args),
+        dry_run: common::build_dry_run(args),
+    })
+}
+
+/// The file libpq reads the services from.
+fn get_service_file_path() -> Result<PathBuf, CliError> {
+    match env::var(ENV_SERVICE_FILE_PATH) {
+        Ok(path) => Ok(PathBuf::from(path)),
+        Err(_) => Ok(homedir::get_my_home()?
+            .ok_or(CliError::HomeDirNotFound)?
+            .join(SERVICE_FILE_NAME)),
+    }
+}
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+use clap::ArgMatches;
+
+use crate::tools::validate::Validate;
+
+pub fn build(args: &ArgMatches) -> Validate {
+    let (_, validate_args) = args.subcommand().unwrap();
+    Validate {
+        path: validate_args.get_one::<PathBuf>("file").cloned(),
+    }
+}
@@ -0,0 +1,36 @@
+use clap::ArgMatches;
+
+use crate::{
+    cli::{
+        parsers::common::{self, build_login_common_args},
+        Flow,
+    },
+    helpers::{connection_url::UrlFormat, logger::LOG_TO_STDERR},
+    url::Url,
+};
+
+pub fn build(args: &ArgMatches) -> Flow {
+    common::set_debug(args);
+    // The url is usually captured by the shell, keep it the only thing on stdout
+    LOG_TO_STDERR.set(true).unwrap();
+    let login_builder = build_login_common_args(args);
+    let login = if args.get_flag("no-persist") {
+        login_builder.write_to_file(false)
+    } else {
+        login_builder
+    }
+    .build()
+    .unwrap();
+    let datastore_name = args.get_one::<String>("datastore_name").unwrap().to_owned();
+    let format = args
+        .get_one::<String>("format")
+        .map(|format| format.parse::<UrlFormat>().unwrap());
+    Flow::Url(Url {
+        login,
+        datastore_name,
+        datastore_id: args.get_one::<String>("id").cloned(),
+        database: args.get_one::<String>("database").cloned(),
+        format,
+        redact: args.get_flag("redact"),
+    })
+}
@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use crate::login::Login;
+
+use super::errors::EnvError;
+
+#[derive(Debug)]
+pub struct Env {
+    pub login: Login,
+    pub datastore_name: String,
+    /// Selects between datastores sharing the same name.
+    pub datastore_id: Option<String>,
+    pub database: Option<String>,
+    pub shell: EnvShell,
+    /// Print the commands which remove the variables instead of setting them
+    pub unset: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvShell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+    Dotenv,
+}
+
+impl EnvShell {
+    pub const NAMES: [&'static str; 5] = ["bash", "zsh", "fish", "powershell", "dotenv"];
+
+    pub fn export(self, name: &str, value: &str) -> String {
+        match self {
+            Self::Bash | Self::Zsh => format!("export {name}={}", posix_quote(value)),
+            Self::Fish => format!("set -gx {name} {}", fish_quote(value)),
+            Self::PowerShell => format!("$Env:{name} = {}", powershell_quote(value)),
+            Self::Dotenv => format!("{name}={}", dotenv_quote(value)),
+        }
+    }
+
+    pub fn unset(self, name: &str) -> Result<String, EnvError> {
+        match self {
+            Self::Bash | Self::Zsh => Ok(format!("unset {name}")),
+            Self::Fish => Ok(format!("set -e {name}")),
+            Self::PowerShell => Ok(format!(
+                "Remove-Item Env:{name} -ErrorAction SilentlyContinue"
+            )),
+            Self::Dotenv => Err(EnvError::UnsetNotSupported(self)),
+        }
+    }
+}
+
+impl FromStr for EnvShell {
+    type Err = EnvError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            "powershell" => Ok(Self::PowerShell),
+            "dotenv" => Ok(Self::Dotenv),
+            _ => Err(EnvError::UnknownShell(value.to_string())),
+        }
+    }
+}
+
+/// Nothing is expanded inside single quotes, a single quote is closed, escaped and reopened.
+fn posix_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Inside single quotes fish only expands `\'` and `\\`.
+fn fish_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Inside single quotes PowerShell only expands a doubled single quote.
+fn powershell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// dotenv parsers expand escapes and variables inside double quotes.
+fn dotenv_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('$', r"\$")
+        .replace('`', r"\`")
+        .replace('\n', r"\n");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALUE: &str = r#"it's "a" $secret\"#;
+
+    #[test]
+    fn test_export_quoting() {
+        assert_eq!(
+            EnvShell::Bash.export("PGPASSWORD", VALUE),
+            r#"export PGPASSWORD='it'\''s "a" $secret\'"#
+        );
+        assert_eq!(
+            EnvShell::Fish.export("PGPASSWORD", VALUE),
+            r#"set -gx PGPASSWORD 'it\'s "a" $secret\\'"#
+        );
+        assert_eq!(
+            EnvShell::PowerShell.export("PGPASSWORD", VALUE),
+            r#"$Env:PGPASSWORD = 'it''s "a" $secret\'"#
+        );
+        assert_eq!(
+            EnvShell::Dotenv.export("PGPASSWORD", VALUE),
+            r#"PGPASSWORD="it's \"a\" \$secret\\""#
+        );
+    }
+
+    #[test]
+    fn test_unset() {
+        assert_eq!(EnvShell::Zsh.unset("PGHOST").unwrap(), "unset PGHOST");
+        assert_eq!(EnvShell::Fish.unset("PGHOST").unwrap(), "set -e PGHOST");
+        assert!(EnvShell::Dotenv.unset("PGHOST").is_err());
+    }
+}
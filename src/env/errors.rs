@@ -0,0 +1,27 @@
+use crate::{
+    helpers::{
+        connection_url::ConnectionUrlError,
+        datastores::errors::{DatastoresError, ResolveDatastoreError},
+    },
+    login::errors::LoginError,
+};
+
+use super::EnvShell;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EnvError {
+    #[error("Failed to login: {0}")]
+    LoginError(#[from] LoginError),
+    #[error("{0}")]
+    DatastoresError(#[from] DatastoresError),
+    #[error("{0}")]
+    ResolveDatastoreError(#[from] ResolveDatastoreError),
+    #[error("{0}")]
+    ConnectionUrlError(#[from] ConnectionUrlError),
+    #[error("Unknown shell: {0}, expected one of: bash, zsh, fish, powershell, dotenv")]
+    UnknownShell(String),
+    #[error("{0:?} files can't unset variables")]
+    UnsetNotSupported(EnvShell),
+    #[error("Failed to write the environment variables: {0}")]
+    WriteError(std::io::Error),
+}
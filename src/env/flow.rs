@@ -0,0 +1,59 @@
+use zeroize::Zeroizing;
+
+use crate::{
+    helpers::{connection_env, connection_url::ConnectionDetails, datastores},
+    login,
+};
+
+use super::{errors::EnvError, Env};
+
+pub async fn run<R, W>(params: Env, user_input_stream: R, writer: &mut W) -> Result<(), EnvError>
+where
+    R: std::io::BufRead,
+    W: std::io::Write,
+{
+    let lines = if params.unset {
+        get_unset_lines(&params)?
+    } else {
+        get_export_lines(&params, user_input_stream).await?
+    };
+    for line in lines {
+        writeln!(writer, "{}", line.as_str()).map_err(EnvError::WriteError)?;
+    }
+    Ok(())
+}
+
+async fn get_export_lines<R>(
+    params: &Env,
+    user_input_stream: R,
+) -> Result<Vec<Zeroizing<String>>, EnvError>
+where
+    R: std::io::BufRead,
+{
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+    let datastore_info =
+        datastores_info.resolve(&params.datastore_name, params.datastore_id.as_deref())?;
+    let connection_details = ConnectionDetails {
+        datastore_info,
+        username: &credentials.username,
+        password: &credentials.password,
+        database: params.database.as_deref(),
+        redact: false,
+    };
+    Ok(connection_env::get(&connection_details)?
+        .into_iter()
+        .map(|(name, value)| Zeroizing::new(params.shell.export(name, &value)))
+        .collect())
+}
+
+/// Unsetting doesn't need credentials, only the type of the datastore from the cache.
+fn get_unset_lines(params: &Env) -> Result<Vec<Zeroizing<String>>, EnvError> {
+    let datastores_info = datastores::file::load(&params.login.satori_folder_path)?;
+    let datastore_info =
+        datastores_info.resolve(&params.datastore_name, params.datastore_id.as_deref())?;
+    connection_env::get_names(&datastore_info.r#type)
+        .iter()
+        .map(|name| params.shell.unset(name).map(Zeroizing::new))
+        .collect()
+}
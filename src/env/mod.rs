@@ -0,0 +1,6 @@
+pub mod data;
+pub mod errors;
+pub mod flow;
+
+pub use data::{Env, EnvShell};
+pub use flow::run;
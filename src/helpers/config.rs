@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+const CONFIG_FILE_NAME: &str = "config.yaml";
+
+/// User settings, read from `config.yaml` in the Satori folder.
+#[derive(Deserialize, Debug, Default)]
+pub struct Config {
+    /// Additional tool definition files, or directories of such files
+    #[serde(default)]
+    pub tools_paths: Vec<PathBuf>,
+}
+
+/// A missing config file means the default config, an invalid one is reported and ignored.
+pub fn load(satori_folder_path: &Path) -> Config {
+    let config_path = satori_folder_path.join(CONFIG_FILE_NAME);
+    let Ok(content) = std::fs::read_to_string(&config_path) else {
+        return Config::default();
+    };
+    serde_yaml::from_str(&content).unwrap_or_else(|err| {
+        eprintln!(
+            "Ignoring invalid config file {}: {err}",
+            config_path.display()
+        );
+        Config::default()
+    })
+}
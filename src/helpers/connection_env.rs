@@ -0,0 +1,76 @@
+use zeroize::Zeroizing;
+
+use super::connection_url::{ConnectionDetails, ConnectionUrlError, UrlFormat};
+use super::satori_console::DatastoreType;
+
+const POSTGRES_ENV: [&str; 6] = [
+    "PGHOST",
+    "PGPORT",
+    "PGUSER",
+    "PGPASSWORD",
+    "PGDATABASE",
+    "PGCHANNELBINDING",
+];
+const AWS_ENV: [&str; 3] = [
+    "AWS_ACCESS_KEY_ID",
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_ENDPOINT_URL",
+];
+const MONGO_ENV: [&str; 1] = ["MONGODB_URI"];
+
+/// The names of all the environment variables clients of the datastore type read.
+pub fn get_names(datastore_type: &DatastoreType) -> &'static [&'static str] {
+    if datastore_type.is_postgres_dialect() {
+        &POSTGRES_ENV
+    } else if datastore_type.is_aws() {
+        &AWS_ENV
+    } else if datastore_type == &DatastoreType::Mongo {
+        &MONGO_ENV
+    } else {
+        &[]
+    }
+}
+
+/// The environment variables of the connection, the values are zeroed on drop as some of them
+/// hold the password.
+pub fn get(
+    connection_details: &ConnectionDetails,
+) -> Result<Vec<(&'static str, Zeroizing<String>)>, ConnectionUrlError> {
+    let datastore_info = connection_details.datastore_info;
+    let datastore_type = &datastore_info.r#type;
+    let password = || Zeroizing::new(connection_details.password.expose().to_string());
+    if datastore_type.is_postgres_dialect() {
+        let mut env = vec![
+            ("PGHOST", datastore_info.satori_host.clone().into()),
+            ("PGUSER", connection_details.username.to_string().into()),
+            ("PGPASSWORD", password()),
+        ];
+        if let Some(port) = connection_details.port() {
+            env.insert(1, ("PGPORT", port.to_string().into()));
+        }
+        if let Some(database) = connection_details.database {
+            env.push(("PGDATABASE", database.to_string().into()));
+        }
+        env.push(("PGCHANNELBINDING", "disable".to_string().into()));
+        Ok(env)
+    } else if datastore_type.is_aws() {
+        Ok(vec![
+            (
+                "AWS_ACCESS_KEY_ID",
+                connection_details.username.to_string().into(),
+            ),
+            ("AWS_SECRET_ACCESS_KEY", password()),
+            (
+                "AWS_ENDPOINT_URL",
+                format!("https://{}", datastore_info.get_datastore_name()?).into(),
+            ),
+        ])
+    } else if datastore_type == &DatastoreType::Mongo {
+        Ok(vec![(
+            "MONGODB_URI",
+            Zeroizing::new(connection_details.to_url(UrlFormat::Mongodb)?),
+        )])
+    } else {
+        Err(ConnectionUrlError::UnsupportedType(datastore_type.clone()))
+    }
+}
@@ -1,3 +1,4 @@
+use core::fmt;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
@@ -5,7 +6,7 @@ use std::hash::Hash;
 use crate::helpers::satori_console::MongoDeploymentType as SatoriConsoleMongoDeploymentType;
 use crate::helpers::satori_console::{DatastoreAccessDetails, DatastoreSettings, DatastoreType};
 
-use super::errors::{GetHostError, ToDsInfoError};
+use super::errors::{GetHostError, ResolveDatastoreError, ToDsInfoError};
 
 pub type DatastoreName = String;
 
@@ -13,6 +14,9 @@ pub type DatastoreName = String;
 pub struct DatastoresInfo {
     pub account_id: String,
     pub datastores: HashMap<DatastoreName, DatastoreInfo>,
+    /// Datastores the user has access to, but the CLI can't connect to.
+    #[serde(default)]
+    pub unusable_datastores: HashMap<DatastoreName, UnusableDatastore>,
 }
 
 impl DatastoresInfo {
@@ -20,16 +24,46 @@ impl DatastoresInfo {
         account_id: String,
         value: &HashSet<DatastoreAccessDetails>,
     ) -> Self {
-        let datastores = value
-            .iter()
-            .filter_map(|datastore| {
-                let datastore_info = DatastoreInfo::try_from(datastore.clone()).ok()?;
-                Some((datastore.name.clone(), datastore_info))
-            })
-            .collect();
+        let mut names_count: HashMap<&str, usize> = HashMap::new();
+        for datastore in value {
+            *names_count.entry(datastore.name.as_str()).or_default() += 1;
+        }
+        for (name, count) in &names_count {
+            if *count > 1 {
+                log::warn!(
+                    "{count} datastores are named {name}, use {name}#<id> or --id to select one of them"
+                );
+            }
+        }
+
+        let mut datastores = HashMap::new();
+        let mut unusable_datastores = HashMap::new();
+        for datastore in value {
+            // Datastores sharing a name are keyed by name and id, so none of them is lost.
+            let key = if names_count[datastore.name.as_str()] > 1 {
+                format!("{}#{}", datastore.name, datastore.id)
+            } else {
+                datastore.name.clone()
+            };
+            match DatastoreInfo::try_from(datastore.clone()) {
+                Ok(datastore_info) => {
+                    datastores.insert(key, datastore_info);
+                }
+                Err(err) => {
+                    log::debug!("Datastore {} can't be used: {}", datastore.name, err);
+                    let unusable = UnusableDatastore {
+                        id: datastore.id.clone(),
+                        r#type: datastore.r#type.clone(),
+                        reason: err.into(),
+                    };
+                    unusable_datastores.insert(key, unusable);
+                }
+            }
+        }
         Self {
             account_id,
             datastores,
+            unusable_datastores,
         }
     }
 
@@ -37,10 +71,219 @@ impl DatastoresInfo {
     pub fn is_datastores_available(&self) -> bool {
         !self.datastores.is_empty()
     }
+
+    /// Find the datastore the user asked for.
+    ///
+    /// The query is either a key of the cache, or a datastore name optionally followed by
+    /// `#<id>` and/or `@<type>`, e.g. `prod#1234` or `prod@redshift`.
+    /// `id` comes from the `--id` flag and narrows down datastores sharing the same name.
+    pub fn resolve(
+        &self,
+        query: &str,
+        id: Option<&str>,
+    ) -> Result<&DatastoreInfo, ResolveDatastoreError> {
+        let candidates = self.find_candidates(query, id);
+        match candidates.as_slice() {
+            [] => Err(ResolveDatastoreError::NotFound(query.to_string())),
+            [(_, CacheEntry::Usable(datastore_info))] => Ok(datastore_info),
+            [(key, CacheEntry::Unusable(unusable))] => Err(ResolveDatastoreError::Unusable(
+                (*key).to_string(),
+                (*unusable).clone(),
+            )),
+            _ => {
+                let mut keys = candidates
+                    .iter()
+                    .map(|(key, entry)| format!("{key} ({:?})", entry.r#type()))
+                    .collect::<Vec<String>>();
+                keys.sort();
+                Err(ResolveDatastoreError::Ambiguous(
+                    query.to_string(),
+                    keys.join(", "),
+                ))
+            }
+        }
+    }
+
+    /// Find the datastore served by the Satori host, the port is only compared when both are
+    /// known. A host serving more than one matching datastore is ambiguous.
+    pub fn find_by_host(
+        &self,
+        host: &str,
+        port: Option<u16>,
+    ) -> Result<Option<(&str, &DatastoreInfo)>, ResolveDatastoreError> {
+        let candidates = self
+            .datastores
+            .iter()
+            .filter(|(_, info)| {
+                info.satori_host.eq_ignore_ascii_case(host)
+                    && match (port, info.port) {
+                        (Some(port), Some(datastore_port)) => port == datastore_port,
+                        _ => true,
+                    }
+            })
+            .collect::<Vec<_>>();
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [(key, info)] => Ok(Some((key.as_str(), info))),
+            _ => {
+                let mut keys = candidates
+                    .iter()
+                    .map(|(key, info)| format!("{key} ({:?})", info.r#type))
+                    .collect::<Vec<String>>();
+                keys.sort();
+                Err(ResolveDatastoreError::AmbiguousHost(
+                    host.to_string(),
+                    keys.join(", "),
+                ))
+            }
+        }
+    }
+
+    /// Check if more than one datastore in the cache is named like the datastore of `key`.
+    pub fn is_duplicate_name(&self, key: &str) -> bool {
+        let Some((_, entry)) = self.entries().find(|(entry_key, _)| *entry_key == key) else {
+            return false;
+        };
+        let name = display_name(key, entry.id());
+        self.entries()
+            .filter(|(entry_key, entry)| display_name(entry_key, entry.id()) == name)
+            .count()
+            > 1
+    }
+
+    fn find_candidates(&self, query: &str, id: Option<&str>) -> Vec<(&str, CacheEntry<'_>)> {
+        let id_matches =
+            |entry: &CacheEntry<'_>, id: Option<&str>| id.is_none_or(|id| entry.id() == id);
+
+        let exact_match = self
+            .entries()
+            .filter(|(key, entry)| *key == query && id_matches(entry, id))
+            .collect::<Vec<_>>();
+        if !exact_match.is_empty() {
+            return exact_match;
+        }
+
+        let (name, query_type) = match query.rsplit_once('@') {
+            Some((name, query_type)) => (name, Some(query_type)),
+            None => (query, None),
+        };
+        let (name, query_id) = match name.rsplit_once('#') {
+            Some((name, query_id)) => (name, Some(query_id)),
+            None => (name, None),
+        };
+        self.entries()
+            .filter(|(key, entry)| display_name(key, entry.id()) == name)
+            .filter(|(_, entry)| id_matches(entry, query_id) && id_matches(entry, id))
+            .filter(|(_, entry)| {
+                query_type.is_none_or(|query_type| {
+                    normalize_type_name(&format!("{:?}", entry.r#type()))
+                        == normalize_type_name(query_type)
+                })
+            })
+            .collect()
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (&str, CacheEntry<'_>)> {
+        let usable = self
+            .datastores
+            .iter()
+            .map(|(key, info)| (key.as_str(), CacheEntry::Usable(info)));
+        let unusable = self
+            .unusable_datastores
+            .iter()
+            .map(|(key, info)| (key.as_str(), CacheEntry::Unusable(info)));
+        usable.chain(unusable)
+    }
+}
+
+enum CacheEntry<'a> {
+    Usable(&'a DatastoreInfo),
+    Unusable(&'a UnusableDatastore),
+}
+
+impl CacheEntry<'_> {
+    fn id(&self) -> &str {
+        match self {
+            Self::Usable(info) => &info.id,
+            Self::Unusable(info) => &info.id,
+        }
+    }
+
+    fn r#type(&self) -> &DatastoreType {
+        match self {
+            Self::Usable(info) => &info.r#type,
+            Self::Unusable(info) => &info.r#type,
+        }
+    }
+}
+
+/// The name of the datastore as shown in the Satori data portal.
+pub(crate) fn display_name<'a>(key: &'a str, id: &str) -> &'a str {
+    if id.is_empty() {
+        return key;
+    }
+    key.strip_suffix(id)
+        .and_then(|name| name.strip_suffix('#'))
+        .unwrap_or(key)
+}
+
+fn normalize_type_name(type_name: &str) -> String {
+    type_name
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// A datastore which is kept in the cache only to explain why it can't be used.
+#[derive(Deserialize, Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct UnusableDatastore {
+    #[serde(default)]
+    pub id: String,
+    pub r#type: DatastoreType,
+    pub reason: UnusableReason,
+}
+
+#[derive(Deserialize, Debug, Clone, Serialize, Eq, PartialEq)]
+pub enum UnusableReason {
+    UnsupportedType,
+    MissingSatoriHostname,
+    UnknownDeploymentType,
+}
+
+impl From<ToDsInfoError> for UnusableReason {
+    fn from(value: ToDsInfoError) -> Self {
+        match value {
+            ToDsInfoError::UnsupportedType => Self::UnsupportedType,
+            ToDsInfoError::MissingSatoriHostname => Self::MissingSatoriHostname,
+            ToDsInfoError::UnknownDeploymentType => Self::UnknownDeploymentType,
+        }
+    }
+}
+
+impl fmt::Display for UnusableDatastore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reason {
+            UnusableReason::UnsupportedType => write!(
+                f,
+                "{:?} datastores are not supported by the Satori CLI",
+                self.r#type
+            ),
+            UnusableReason::MissingSatoriHostname => write!(
+                f,
+                "the datastore has no Satori hostname, verify that it is connected to a Satori Data Access Controller"
+            ),
+            UnusableReason::UnknownDeploymentType => {
+                write!(f, "the MongoDB deployment type is unknown")
+            }
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone, Serialize, Eq, PartialEq)]
 pub struct DatastoreInfo {
+    #[serde(default)]
+    pub id: String,
     pub satori_host: String,
     pub databases: Vec<String>,
     pub port: Option<u16>,
@@ -57,6 +300,9 @@ impl Hash for DatastoreInfo {
 impl TryFrom<DatastoreAccessDetails> for DatastoreInfo {
     type Error = ToDsInfoError;
     fn try_from(value: DatastoreAccessDetails) -> Result<Self, Self::Error> {
+        if !value.r#type.is_datastore_supported() {
+            return Err(ToDsInfoError::UnsupportedType);
+        }
         let deployment_type = value.datastore_settings.map(MongoDeploymentType::try_from);
         let deployment_type = match deployment_type {
             Some(Ok(deployment_type)) => Some(deployment_type),
@@ -67,6 +313,7 @@ impl TryFrom<DatastoreAccessDetails> for DatastoreInfo {
             .satori_hostname
             .ok_or(ToDsInfoError::MissingSatoriHostname)?;
         Ok(DatastoreInfo {
+            id: value.id,
             satori_host,
             databases: value.dbs,
             port: value.port,
@@ -111,3 +358,164 @@ impl TryFrom<DatastoreSettings> for MongoDeploymentType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_details(
+        id: &str,
+        name: &str,
+        r#type: DatastoreType,
+        host: Option<&str>,
+    ) -> DatastoreAccessDetails {
+        DatastoreAccessDetails {
+            id: id.to_string(),
+            name: name.to_string(),
+            r#type,
+            satori_hostname: host.map(str::to_string),
+            port: Some(5432),
+            satori_auth_enabled: true,
+            datastore_settings: None,
+            dbs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_unusable_datastores_kept() {
+        let response = HashSet::from([
+            access_details(
+                "1",
+                "postgres",
+                DatastoreType::Postgresql,
+                Some("pg.example.com"),
+            ),
+            access_details(
+                "2",
+                "snowflake",
+                DatastoreType::Snowflake,
+                Some("sf.example.com"),
+            ),
+            access_details("3", "no_host", DatastoreType::Postgresql, None),
+        ]);
+        let info = DatastoresInfo::new_from_console_response("account_id".to_string(), &response);
+
+        assert_eq!(info.datastores.len(), 1);
+        assert!(info.datastores.contains_key("postgres"));
+        assert_eq!(
+            info.unusable_datastores.get("snowflake").unwrap().reason,
+            UnusableReason::UnsupportedType
+        );
+        assert_eq!(
+            info.unusable_datastores.get("no_host").unwrap().reason,
+            UnusableReason::MissingSatoriHostname
+        );
+    }
+
+    fn duplicate_names_response() -> HashSet<DatastoreAccessDetails> {
+        HashSet::from([
+            access_details(
+                "1",
+                "prod",
+                DatastoreType::Postgresql,
+                Some("pg.example.com"),
+            ),
+            access_details("2", "prod", DatastoreType::Redshift, Some("rs.example.com")),
+            access_details(
+                "3",
+                "prod",
+                DatastoreType::Snowflake,
+                Some("sf.example.com"),
+            ),
+            access_details(
+                "4",
+                "dev",
+                DatastoreType::Postgresql,
+                Some("dev.example.com"),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_duplicate_names_kept() {
+        let info = DatastoresInfo::new_from_console_response(
+            "account_id".to_string(),
+            &duplicate_names_response(),
+        );
+
+        let mut keys = info.datastores.keys().cloned().collect::<Vec<String>>();
+        keys.sort();
+        assert_eq!(keys, vec!["dev", "prod#1", "prod#2"]);
+        assert!(info.unusable_datastores.contains_key("prod#3"));
+        assert!(info.is_duplicate_name("prod#1"));
+        assert!(!info.is_duplicate_name("dev"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let info = DatastoresInfo::new_from_console_response(
+            "account_id".to_string(),
+            &duplicate_names_response(),
+        );
+
+        assert_eq!(info.resolve("dev", None).unwrap().id, "4");
+        assert_eq!(info.resolve("prod#2", None).unwrap().id, "2");
+        assert_eq!(info.resolve("prod", Some("1")).unwrap().id, "1");
+        assert_eq!(info.resolve("prod@redshift", None).unwrap().id, "2");
+        assert_eq!(info.resolve("prod@POSTGRESQL", None).unwrap().id, "1");
+        assert!(matches!(
+            info.resolve("prod", None),
+            Err(ResolveDatastoreError::Ambiguous(_, _))
+        ));
+        assert!(matches!(
+            info.resolve("prod@snowflake", None),
+            Err(ResolveDatastoreError::Unusable(_, _))
+        ));
+        assert!(matches!(
+            info.resolve("prod", Some("4")),
+            Err(ResolveDatastoreError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_find_by_host() {
+        let mut other_port = access_details(
+            "2",
+            "redshift",
+            DatastoreType::Redshift,
+            Some("pg.example.com"),
+        );
+        other_port.port = Some(5439);
+        let info = DatastoresInfo::new_from_console_response(
+            "account_id".to_string(),
+            &HashSet::from([
+                access_details(
+                    "1",
+                    "postgres",
+                    DatastoreType::Postgresql,
+                    Some("pg.example.com"),
+                ),
+                other_port,
+            ]),
+        );
+
+        let find = |host, port| {
+            info.find_by_host(host, port)
+                .map(|found| found.map(|(key, _)| key))
+        };
+        assert_eq!(
+            find("PG.example.com", Some(5432)).unwrap(),
+            Some("postgres")
+        );
+        assert_eq!(
+            find("pg.example.com", Some(5439)).unwrap(),
+            Some("redshift")
+        );
+        assert_eq!(find("pg.example.com", Some(1234)).unwrap(), None);
+        assert_eq!(find("other.example.com", None).unwrap(), None);
+        assert!(matches!(
+            find("pg.example.com", None),
+            Err(ResolveDatastoreError::AmbiguousHost(_, _))
+        ));
+    }
+}
@@ -2,6 +2,8 @@ use thiserror::Error;
 
 use crate::helpers::{default_app_folder::DefaultFolderError, satori_console};
 
+use super::UnusableDatastore;
+
 #[derive(Debug, Error)]
 pub enum DatastoresError {
     #[error("{0}")]
@@ -26,8 +28,22 @@ pub enum GetHostError {
 
 #[derive(Debug, Error)]
 pub enum ToDsInfoError {
+    #[error("Unsupported datastore type")]
+    UnsupportedType,
     #[error("Missing satori hostname")]
     MissingSatoriHostname,
     #[error("Unknown deployment type")]
     UnknownDeploymentType,
 }
+
+#[derive(Debug, Error)]
+pub enum ResolveDatastoreError {
+    #[error("Datastore: {0} not found in datastores info file")]
+    NotFound(String),
+    #[error("Datastore: {0} can't be used: {1}")]
+    Unusable(String, UnusableDatastore),
+    #[error("Datastore name {0} is ambiguous, use one of: {1}")]
+    Ambiguous(String, String),
+    #[error("Host {0} serves more than one datastore: {1}")]
+    AmbiguousHost(String, String),
+}
@@ -2,16 +2,12 @@ pub mod data;
 pub mod errors;
 pub mod file;
 
-use std::collections::HashSet;
-
 pub use data::*;
 
-use crate::helpers::satori_console;
-
-use super::satori_console::DatastoreAccessDetails;
+use crate::helpers::{satori_console, secret::Secret};
 
 pub async fn get_from_console(
-    jwt: &str,
+    jwt: &Secret,
     domain: &str,
     client_id: &str,
     account_id: String,
@@ -19,17 +15,6 @@ pub async fn get_from_console(
 ) -> Result<DatastoresInfo, errors::DatastoresError> {
     let res =
         satori_console::datastores_access_details(domain, client_id, jwt, invalid_cert).await?;
-    let filtered_datastores = res
-        .iter()
-        .filter_map(|datastore| {
-            if datastore.r#type.is_datastore_supported() {
-                Some(datastore.clone())
-            } else {
-                None
-            }
-        })
-        .collect::<HashSet<DatastoreAccessDetails>>();
-    let datastores_info =
-        DatastoresInfo::new_from_console_response(account_id, &filtered_datastores);
+    let datastores_info = DatastoresInfo::new_from_console_response(account_id, &res);
     Ok(datastores_info)
 }
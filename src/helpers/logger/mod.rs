@@ -4,6 +4,8 @@ use env_logger::Builder;
 use log::LevelFilter;
 
 pub static DEBUG: OnceLock<bool> = OnceLock::new();
+/// Commands whose stdout is consumed by other programs log to stderr instead.
+pub static LOG_TO_STDERR: OnceLock<bool> = OnceLock::new();
 
 pub fn init() {
     let mut builder = Builder::new();
@@ -17,7 +19,11 @@ pub fn init() {
     builder.format_timestamp(None);
     builder.format_level(false);
     builder.format_target(false);
-    builder.target(env_logger::Target::Stdout);
+    if *LOG_TO_STDERR.get_or_init(|| false) {
+        builder.target(env_logger::Target::Stderr);
+    } else {
+        builder.target(env_logger::Target::Stdout);
+    }
 
     builder.init();
 }
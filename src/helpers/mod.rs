@@ -1,5 +1,10 @@
+pub mod config;
+pub mod connection_env;
+pub mod connection_url;
 pub mod datastores;
 pub mod default_app_folder;
+pub mod dry_run;
 pub mod logger;
 pub mod satori_console;
+pub mod secret;
 pub mod tools;
@@ -6,6 +6,8 @@ use reqwest::{
 };
 use serde::de::DeserializeOwned;
 
+use crate::helpers::secret::Secret;
+
 use super::{
     errors::{self, SatoriError},
     DatabaseCredentials, DatastoreAccessDetails, DatastoreAccessDetailsDbs, OauthResponse,
@@ -57,7 +59,7 @@ pub async fn generate_token_oauth(
 pub async fn get_database_credentials(
     domain: &str,
     client_id: &str,
-    jwt: &str,
+    jwt: &Secret,
     user_id: &str,
     invalid_cert: bool,
 ) -> Result<DatabaseCredentials, SatoriError> {
@@ -76,7 +78,7 @@ pub async fn get_database_credentials(
 pub async fn get_user_info(
     domain: &str,
     client_id: &str,
-    jwt: &str,
+    jwt: &Secret,
     invalid_cert: bool,
 ) -> Result<UserProfile, SatoriError> {
     let address = format!("{domain}/api/users/me/profile");
@@ -97,7 +99,7 @@ pub async fn get_user_info(
 pub async fn datastores_access_details(
     domain: &str,
     client_id: &str,
-    jwt: &str,
+    jwt: &Secret,
     invalid_cert: bool,
 ) -> Result<HashSet<DatastoreAccessDetails>, SatoriError> {
     let mut page: u8 = 0;
@@ -139,7 +141,7 @@ pub async fn datastores_access_details(
 async fn get_datastore_access_details_internal(
     address: &str,
     client_id: &str,
-    jwt: &str,
+    jwt: &Secret,
     invalid_cert: bool,
     page: u8,
 ) -> Result<DatastoreAccessDetailsDbs, SatoriError> {
@@ -165,7 +167,7 @@ async fn get_datastore_access_details_internal(
 async fn make_request<T, U>(
     url: U,
     method: reqwest::Method,
-    jwt: &str,
+    jwt: &Secret,
     client_id: &str,
     invalid_cert: bool,
     expected_status_code: StatusCode,
@@ -189,9 +191,12 @@ where
     res.json::<T>().await.map_err(SatoriError::Json)
 }
 
-fn get_headers_with_jwt(client_id: &str, jwt: &str) -> HeaderMap {
+fn get_headers_with_jwt(client_id: &str, jwt: &Secret) -> HeaderMap {
     let mut headers = get_headers_no_jwt(client_id);
-    headers.insert(AUTHORIZATION, format!("Bearer {jwt}").parse().unwrap());
+    headers.insert(
+        AUTHORIZATION,
+        format!("Bearer {}", jwt.expose()).parse().unwrap(),
+    );
     headers
 }
 
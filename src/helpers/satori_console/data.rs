@@ -1,29 +1,21 @@
-use core::fmt;
 use std::hash::Hash;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Clone, Serialize)]
+use crate::helpers::secret::Secret;
+
+#[derive(Deserialize, Clone, Serialize, Debug)]
 pub struct DatabaseCredentials {
     pub username: String,
-    pub password: String,
+    pub password: Secret,
     #[serde(rename = "expiredAt", with = "chrono::serde::ts_milliseconds")]
     pub expires_at: DateTime<Utc>,
 }
-impl fmt::Debug for DatabaseCredentials {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("DatabaseCredentials")
-            .field("username", &self.username)
-            .field("password", &"*********")
-            .field("expires_at", &self.expires_at)
-            .finish()
-    }
-}
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct OauthResponse {
-    pub access_token: String,
+    pub access_token: Secret,
     pub token_type: String,
     pub expires_in: u32,
 }
@@ -138,6 +130,18 @@ impl DatastoreType {
             || self == &DatastoreType::Redshift
             || self == &DatastoreType::Mongo
     }
+
+    /// The port clients use when the datastore doesn't define one.
+    pub fn default_port(&self) -> Option<u16> {
+        match self {
+            DatastoreType::Postgresql | DatastoreType::Greenplum => Some(5432),
+            DatastoreType::Redshift => Some(5439),
+            DatastoreType::CockroachDb => Some(26257),
+            DatastoreType::Mongo => Some(27017),
+            DatastoreType::Athena | DatastoreType::S3 => Some(443),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+use crate::helpers::satori_console::DatastoreType;
+
+#[derive(Debug, Error)]
+pub enum ToolsDataError {
+    #[error("Failed to parse the built-in tools definitions: {0}")]
+    BuiltInParseError(serde_yaml::Error),
+    #[error("Tool {0} wasn't found, run `satori tools validate` to check the tools definitions")]
+    ToolNotFound(String),
+    #[error("No tool is defined for {0:?} datastores, use `satori run <tool>` or add {0:?} to the supported_types of a tool")]
+    NoDefaultTool(DatastoreType),
+}
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use crate::helpers::config;
+
+use super::Tool;
+
+const USER_TOOLS_DIR: &str = "tools.d";
+
+/// Tool definitions the user added to `tools.d` in the Satori folder, and to the `tools_paths` of the config.
+/// Files which can't be read or parsed are reported and skipped.
+pub fn load_user_tools(satori_folder_path: &Path) -> Vec<Tool> {
+    get_user_tools_files(satori_folder_path)
+        .iter()
+        .flat_map(|path| load_tools_file(path))
+        .collect()
+}
+
+/// The files of the user tools, in the order they are loaded.
+pub fn get_user_tools_files(satori_folder_path: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![satori_folder_path.join(USER_TOOLS_DIR)];
+    paths.extend(config::load(satori_folder_path).tools_paths);
+
+    paths.iter().flat_map(|path| get_tool_files(path)).collect()
+}
+
+/// A user tool replaces the built-in tool with the same name, other tools are added.
+pub fn merge(tools: &mut Vec<Tool>, user_tools: Vec<Tool>) {
+    for user_tool in user_tools {
+        match tools.iter_mut().find(|tool| tool.name == user_tool.name) {
+            Some(tool) => {
+                log::debug!("Tool {} is overridden by a user definition", tool.name);
+                *tool = user_tool;
+            }
+            None => tools.push(user_tool),
+        }
+    }
+}
+
+/// A path is either a tools file, or a directory of `.yaml`/`.yml` tools files loaded by name order.
+fn get_tool_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        log::debug!("Tools path {} doesn't exist", path.display());
+        return vec![];
+    };
+    let mut files = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == "yaml" || extension == "yml")
+        })
+        .collect::<Vec<PathBuf>>();
+    files.sort();
+    files
+}
+
+fn load_tools_file(path: &Path) -> Vec<Tool> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Skipping tools file {}: {err}", path.display());
+            return vec![];
+        }
+    };
+    match serde_yaml::from_str::<Vec<Tool>>(&content) {
+        Ok(tools) => tools,
+        Err(err) => {
+            eprintln!("Skipping invalid tools file {}: {err}", path.display());
+            vec![]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PGCLI: &str = "- name: pgcli
+  command: pgcli
+  command_args: -h {{ host }}
+";
+    const PSQL_OVERRIDE: &str = "- name: psql
+  command: /opt/bin/psql
+  command_args: -h {{ host }}
+";
+
+    #[test]
+    fn test_load_user_tools() {
+        let satori_folder = tempfile::tempdir().unwrap();
+        let tools_dir = satori_folder.path().join(USER_TOOLS_DIR);
+        std::fs::create_dir(&tools_dir).unwrap();
+        std::fs::write(tools_dir.join("pgcli.yaml"), PGCLI).unwrap();
+        std::fs::write(tools_dir.join("broken.yaml"), "- name: [").unwrap();
+        std::fs::write(tools_dir.join("notes.txt"), "not a tool").unwrap();
+        let extra_file = satori_folder.path().join("psql.yml");
+        std::fs::write(&extra_file, PSQL_OVERRIDE).unwrap();
+        std::fs::write(
+            satori_folder.path().join("config.yaml"),
+            format!("tools_paths:\n  - {}\n", extra_file.display()),
+        )
+        .unwrap();
+
+        let user_tools = load_user_tools(satori_folder.path());
+
+        let names = user_tools
+            .iter()
+            .map(|tool| tool.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["pgcli", "psql"]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut tools =
+            serde_yaml::from_str::<Vec<Tool>>(crate::helpers::tools::TOOLS_DATA).unwrap();
+        let built_in_count = tools.len();
+        let mut user_tools = serde_yaml::from_str::<Vec<Tool>>(PSQL_OVERRIDE).unwrap();
+        user_tools.extend(serde_yaml::from_str::<Vec<Tool>>(PGCLI).unwrap());
+
+        merge(&mut tools, user_tools);
+
+        assert_eq!(tools.len(), built_in_count + 1);
+        let psql = tools.iter().find(|tool| tool.name == "psql").unwrap();
+        assert_eq!(psql.command, "/opt/bin/psql");
+        assert_eq!(tools.last().unwrap().name, "pgcli");
+    }
+}
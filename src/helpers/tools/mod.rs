@@ -0,0 +1,345 @@
+pub mod errors;
+pub mod file;
+pub mod template;
+
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use super::{default_app_folder, satori_console::DatastoreType};
+
+use errors::ToolsDataError;
+
+pub const TOOLS_DATA: &str = include_str!("../../../configurations/tools.yaml");
+
+/// The variables the tools templates can use, beside the tool args.
+pub const CONTEXT_VARIABLES: [&str; 14] = [
+    "host",
+    "user",
+    "password",
+    "database",
+    "port",
+    "datastore_name",
+    "datastore_id",
+    "datastore_type",
+    "satori_host",
+    "databases",
+    "deployment_type",
+    "account_id",
+    "expires_at",
+    "files",
+];
+
+/// Names of the arguments the run command already defines for every tool.
+pub const RESERVED_ARG_NAMES: [&str; 14] = [
+    "datastore_name",
+    "id",
+    "additional_args",
+    "no-launch-browser",
+    "domain",
+    "invalid_cert",
+    "debug",
+    "refresh",
+    "no-persist",
+    "exec",
+    "dry-run",
+    "print-command",
+    "plan-format",
+    "help",
+];
+
+static TOOLS: OnceLock<Tools> = OnceLock::new();
+
+/// The built-in tools, merged with the tools defined by the user.
+pub fn get_or_init() -> Result<&'static Tools, ToolsDataError> {
+    if let Some(tools) = TOOLS.get() {
+        return Ok(tools);
+    }
+    let mut tools = get_built_in()?;
+    match default_app_folder::get() {
+        Ok(satori_folder_path) => {
+            file::merge(&mut tools.value, file::load_user_tools(&satori_folder_path));
+        }
+        Err(err) => log::debug!("Skipping user tools: {err}"),
+    }
+    Ok(TOOLS.get_or_init(|| tools))
+}
+
+pub fn get_built_in() -> Result<Tools, ToolsDataError> {
+    serde_yaml::from_str::<Tools>(TOOLS_DATA).map_err(ToolsDataError::BuiltInParseError)
+}
+
+pub fn get_tool(tool_name: &str) -> Result<&'static Tool, ToolsDataError> {
+    get_or_init()?
+        .value
+        .iter()
+        .find(|tool| tool.name == tool_name)
+        .ok_or_else(|| ToolsDataError::ToolNotFound(tool_name.to_string()))
+}
+
+/// The tool `satori connect` uses for the datastore type, a tool which lists the type in
+/// `default_for` is preferred over the first tool which lists it in `supported_types`.
+pub fn get_default_tool(datastore_type: &DatastoreType) -> Result<&'static Tool, ToolsDataError> {
+    let tools = &get_or_init()?.value;
+    tools
+        .iter()
+        .find(|tool| tool.default_for.contains(datastore_type))
+        .or_else(|| {
+            tools
+                .iter()
+                .find(|tool| tool.supported_types.contains(datastore_type))
+        })
+        .ok_or_else(|| ToolsDataError::NoDefaultTool(datastore_type.clone()))
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(transparent)]
+pub struct Tools {
+    pub value: Vec<Tool>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Tool {
+    pub name: String,
+    #[serde(default = "Vec::new")]
+    pub cli_args: Vec<CliArgs>,
+    pub command: String,
+    pub command_args: CommandArgs,
+    #[serde(default = "Vec::new")]
+    pub env: Vec<EnvTool>,
+    /// Files written before the tool runs and removed when it exits.
+    #[serde(default = "Vec::new")]
+    pub files: Vec<ToolFile>,
+    /// The datastore types the tool can connect to, any type when empty.
+    #[serde(default = "Vec::new")]
+    pub supported_types: Vec<DatastoreType>,
+    /// The datastore types `satori connect` uses this tool for.
+    #[serde(default = "Vec::new")]
+    pub default_for: Vec<DatastoreType>,
+}
+
+impl Tool {
+    pub fn supports(&self, datastore_type: &DatastoreType) -> bool {
+        self.supported_types.is_empty() || self.supported_types.contains(datastore_type)
+    }
+
+    pub fn get_env(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|env| (env.name.clone(), env.value.clone()))
+            .collect::<Vec<(String, String)>>()
+    }
+
+    /// The jinja templates of the tool, with a name to locate each of them in the definition.
+    pub fn get_templates(&self) -> Vec<(String, &str)> {
+        let mut templates = match &self.command_args {
+            CommandArgs::Line(line) => vec![("command_args".to_string(), line.as_str())],
+            CommandArgs::List(args) => args
+                .iter()
+                .enumerate()
+                .flat_map(|(index, arg)| arg.get_templates(index))
+                .collect(),
+        };
+        templates.extend(
+            self.env
+                .iter()
+                .map(|env| (format!("env.{}", env.name), env.value.as_str())),
+        );
+        templates.extend(
+            self.files
+                .iter()
+                .map(|file| (format!("files.{}", file.name), file.content.as_str())),
+        );
+        templates
+    }
+
+    /// The `command_args` templates which put the password on the command line, where other
+    /// users of the host can see it.
+    pub fn get_args_exposing_password(&self) -> Vec<&str> {
+        let env = template::get_environment();
+        self.get_templates()
+            .into_iter()
+            .filter(|(name, _)| name.starts_with("command_args"))
+            .filter(|(name, source)| {
+                env.template_from_named_str(name, source)
+                    .map(|template| {
+                        template.undeclared_variables(false).iter().any(|variable| {
+                            variable == "password"
+                                || template::URL_FUNCTIONS.contains(&variable.as_str())
+                        })
+                    })
+                    .unwrap_or(false)
+            })
+            .map(|(_, source)| source)
+            .collect()
+    }
+}
+
+/// The arguments of the tool command, as jinja templates.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum CommandArgs {
+    /// Each element is rendered into one argument, or a group of arguments.
+    List(Vec<CommandArg>),
+    /// Rendered into a single string, which is split on whitespaces.
+    Line(String),
+}
+
+/// An element of the `command_args` list, dropped when any of its templates renders to an empty
+/// string, `none` values render to an empty string.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum CommandArg {
+    Single(String),
+    /// Arguments which are passed or dropped together, such as a flag and its value.
+    Group(Vec<String>),
+}
+
+impl CommandArg {
+    /// The templates with their name, `command_args[<index>]`, followed by the index in the
+    /// group for groups.
+    pub fn get_templates(&self, index: usize) -> Vec<(String, &str)> {
+        match self {
+            Self::Single(template) => vec![(format!("command_args[{index}]"), template.as_str())],
+            Self::Group(templates) => templates
+                .iter()
+                .enumerate()
+                .map(|(group_index, template)| {
+                    (
+                        format!("command_args[{index}][{group_index}]"),
+                        template.as_str(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EnvTool {
+    pub name: String,
+    pub value: String,
+}
+
+/// A file with owner only permissions, its path is available in the templates as `files.<name>`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ToolFile {
+    pub name: String,
+    /// Some tools require an extension, for example `.js`.
+    #[serde(default)]
+    pub suffix: String,
+    /// Jinja template of the file content.
+    pub content: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct CliArgs {
+    pub name: String,
+    pub help: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub r#type: CliArgType,
+    /// Short flag of an option or a flag, for example `r` for `-r`.
+    pub short: Option<char>,
+    /// Used when an option or a positional arg isn't given.
+    pub default: Option<String>,
+    /// The only values accepted by an option or a positional arg.
+    #[serde(default = "Vec::new")]
+    pub choices: Vec<String>,
+}
+
+impl CliArgs {
+    /// The name of the arg in the jinja context, `read-preference` is available as `read_preference`.
+    pub fn context_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CliArgType {
+    /// `satori run <tool> <datastore> <value>`
+    #[default]
+    Positional,
+    /// `--name <value>`
+    Option,
+    /// `--name`, true when given.
+    Flag,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_parse_tools_file() {
+        serde_yaml::from_str::<Tools>(TOOLS_DATA).unwrap();
+    }
+
+    #[test]
+    fn test_parse_command_args_forms() {
+        let tools = serde_yaml::from_str::<Tools>(
+            "- name: line
+  command: line
+  command_args: -h {{ host }}
+- name: list
+  command: list
+  command_args: [-h, '{{ host }}']
+",
+        )
+        .unwrap();
+        assert!(matches!(tools.value[0].command_args, CommandArgs::Line(_)));
+        assert!(matches!(tools.value[1].command_args, CommandArgs::List(_)));
+    }
+
+    #[test]
+    fn test_args_exposing_password() {
+        let tools = serde_yaml::from_str::<Tools>(
+            "- name: custom
+  command: custom
+  command_args: ['--password={{ password }}', '{{ pg_uri() }}', '{{ user }}']
+  env:
+    - name: PASSWORD
+      value: '{{ password }}'
+",
+        )
+        .unwrap();
+        assert_eq!(
+            tools.value[0].get_args_exposing_password(),
+            vec!["--password={{ password }}", "{{ pg_uri() }}"]
+        );
+        let built_in = get_built_in().unwrap();
+        assert!(built_in
+            .value
+            .iter()
+            .all(|tool| tool.get_args_exposing_password().is_empty()));
+    }
+
+    #[test]
+    fn test_parse_cli_args_types() {
+        let tools = serde_yaml::from_str::<Tools>(
+            "- name: mongosh
+  command: mongosh
+  command_args: ['{{ host }}', '--readPreference={{ read_preference }}']
+  cli_args:
+    - name: read-preference
+      help: Read preference
+      type: option
+      default: primary
+      choices: [primary, secondary]
+    - name: quiet
+      help: Quiet
+      type: flag
+      short: q
+",
+        )
+        .unwrap();
+        let cli_args = &tools.value[0].cli_args;
+        assert_eq!(cli_args[0].r#type, CliArgType::Option);
+        assert_eq!(cli_args[0].context_name(), "read_preference");
+        assert_eq!(cli_args[0].choices, vec!["primary", "secondary"]);
+        assert_eq!(cli_args[1].r#type, CliArgType::Flag);
+        assert_eq!(cli_args[1].short, Some('q'));
+        assert!(!cli_args[1].required);
+    }
+}
@@ -1,6 +1,9 @@
+pub mod env;
 pub mod helpers;
 pub mod list;
 pub mod login;
 pub mod pwd;
 pub mod run;
+pub mod status;
 pub mod tools;
+pub mod url;
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 type DatastoreName = String;
+type ToolName = String;
 
 #[derive(Debug)]
 pub struct List {
@@ -10,6 +11,9 @@ pub struct List {
 
 #[derive(Debug)]
 pub enum ResourceType {
-    Datastores,
+    /// Only the datastores the tool supports, when a tool is given
+    Datastores(Option<ToolName>),
+    /// All the datastores, including the ones which can't be used
+    AllDatastores,
     Databases(DatastoreName),
 }
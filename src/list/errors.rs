@@ -2,6 +2,8 @@
 pub enum ListErrors {
     #[error("{0}")]
     DatastoresError(#[from] crate::helpers::datastores::errors::DatastoresError),
-    #[error("Datastore: {0} not found in datastores.json file")]
-    DatastoreNotFound(String),
+    #[error("{0}")]
+    ResolveDatastoreError(#[from] crate::helpers::datastores::errors::ResolveDatastoreError),
+    #[error("{0}")]
+    ToolsDataError(#[from] crate::helpers::tools::errors::ToolsDataError),
 }
@@ -1,5 +1,7 @@
 use std::path::Path;
 
+use crate::helpers::tools;
+
 use super::{data::List, errors::ListErrors, ResourceType};
 
 pub fn run<W>(params: List, writer: &mut W) -> Result<(), ListErrors>
@@ -7,25 +9,68 @@ where
     W: std::io::Write,
 {
     match params.resource_type {
-        ResourceType::Datastores => handle_datastores(&params.satori_folder_path, writer),
+        ResourceType::Datastores(tool_name) => {
+            handle_datastores(&params.satori_folder_path, tool_name.as_deref(), writer)
+        }
+        ResourceType::AllDatastores => handle_all_datastores(&params.satori_folder_path, writer),
         ResourceType::Databases(datastore_name) => {
             handle_databases(&datastore_name, &params.satori_folder_path, writer)
         }
     }
 }
 
-fn handle_datastores<W>(path: &Path, writer: &mut W) -> Result<(), ListErrors>
+fn handle_datastores<W>(
+    path: &Path,
+    tool_name: Option<&str>,
+    writer: &mut W,
+) -> Result<(), ListErrors>
 where
     W: std::io::Write,
 {
     let info = crate::helpers::datastores::file::load(path)?;
-    let datastores_name = info
+    let tool = tool_name.map(tools::get_tool).transpose()?;
+    let mut datastores_name = info
         .datastores
-        .keys()
-        .map(std::string::ToString::to_string)
-        .collect::<Vec<String>>()
-        .join("\n");
-    writeln!(writer, "{datastores_name}").expect("Failed to write");
+        .iter()
+        .filter(|(_, datastore_info)| tool.is_none_or(|tool| tool.supports(&datastore_info.r#type)))
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<String>>();
+    datastores_name.sort();
+    writeln!(writer, "{}", datastores_name.join("\n")).expect("Failed to write");
+    Ok(())
+}
+
+fn handle_all_datastores<W>(path: &Path, writer: &mut W) -> Result<(), ListErrors>
+where
+    W: std::io::Write,
+{
+    let info = crate::helpers::datastores::file::load(path)?;
+    let mut datastores_name = info
+        .datastores
+        .iter()
+        .map(|(name, datastore_info)| {
+            if info.is_duplicate_name(name) {
+                format!("{name} (duplicate name, {:?})", datastore_info.r#type)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect::<Vec<String>>();
+    datastores_name.sort();
+    let mut unusable_datastores = info
+        .unusable_datastores
+        .iter()
+        .map(|(name, unusable)| {
+            if info.is_duplicate_name(name) {
+                format!("{name} (duplicate name, unusable: {unusable})")
+            } else {
+                format!("{name} (unusable: {unusable})")
+            }
+        })
+        .collect::<Vec<String>>();
+    unusable_datastores.sort();
+    datastores_name.extend(unusable_datastores);
+    writeln!(writer, "{}", datastores_name.join("\n")).expect("Failed to write");
     Ok(())
 }
 
@@ -34,14 +79,10 @@ where
     W: std::io::Write,
 {
     let info = crate::helpers::datastores::file::load(path)?;
-    let database_name = info.datastores.get(datastore_name);
-    if let Some(database_name) = database_name {
-        let databases_name = database_name.databases.join("\n");
-        writeln!(writer, "{databases_name}").expect("Failed to write");
-        Ok(())
-    } else {
-        Err(ListErrors::DatastoreNotFound(datastore_name.to_string()))
-    }
+    let datastore_info = info.resolve(datastore_name, None)?;
+    let databases_name = datastore_info.databases.join("\n");
+    writeln!(writer, "{databases_name}").expect("Failed to write");
+    Ok(())
 }
 
 #[cfg(test)]
@@ -53,13 +94,58 @@ mod tests {
         let mut buffer = Vec::new();
         let datastores_json_path = Path::new("src/list/tests_files/");
         let params = List {
-            resource_type: ResourceType::Datastores,
+            resource_type: ResourceType::Datastores(None),
+            satori_folder_path: datastores_json_path.to_path_buf(),
+        };
+
+        run(params, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output,
+            "dataStoreName\nprod#prodPostgresId\nprod#prodRedshiftId\n"
+        );
+    }
+
+    #[test]
+    fn test_datastores_supported_by_tool() {
+        let mut buffer = Vec::new();
+        let datastores_json_path = Path::new("src/list/tests_files/");
+        let params = List {
+            resource_type: ResourceType::Datastores(Some("psql".to_string())),
+            satori_folder_path: datastores_json_path.to_path_buf(),
+        };
+
+        run(params, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output,
+            "dataStoreName\nprod#prodPostgresId\nprod#prodRedshiftId\n"
+        );
+
+        let mut buffer = Vec::new();
+        let params = List {
+            resource_type: ResourceType::Datastores(Some("mongosh".to_string())),
+            satori_folder_path: datastores_json_path.to_path_buf(),
+        };
+        run(params, &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "\n");
+    }
+
+    #[test]
+    fn test_all_datastores() {
+        let mut buffer = Vec::new();
+        let datastores_json_path = Path::new("src/list/tests_files/");
+        let params = List {
+            resource_type: ResourceType::AllDatastores,
             satori_folder_path: datastores_json_path.to_path_buf(),
         };
 
         run(params, &mut buffer).unwrap();
         let output = String::from_utf8(buffer).unwrap();
-        assert_eq!(output, "dataStoreName\n");
+        assert_eq!(
+            output,
+            "dataStoreName\nprod#prodPostgresId (duplicate name, Postgresql)\nprod#prodRedshiftId (duplicate name, Redshift)\nsnowflakeName (unusable: Snowflake datastores are not supported by the Satori CLI)\n"
+        );
     }
 
     #[test]
@@ -74,4 +160,17 @@ mod tests {
         let output = String::from_utf8(buffer).unwrap();
         assert_eq!(output, "postgres\n");
     }
+
+    #[test]
+    fn test_database_duplicate_name() {
+        let mut buffer = Vec::new();
+        let datastores_json_path = Path::new("src/list/tests_files/");
+        let params = List {
+            resource_type: ResourceType::Databases("prod@redshift".to_string()),
+            satori_folder_path: datastores_json_path.to_path_buf(),
+        };
+        run(params, &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "dev\n");
+    }
 }
@@ -1,4 +1,3 @@
-use core::fmt;
 use std::{path::PathBuf, sync::OnceLock};
 
 use chrono::{DateTime, Utc};
@@ -8,9 +7,10 @@ use serde::{Deserialize, Serialize};
 use crate::helpers::{
     default_app_folder::{self, DefaultFolderError},
     satori_console::DatabaseCredentials,
+    secret::Secret,
 };
 
-pub type Jwt = String;
+pub type Jwt = Secret;
 
 pub(super) const CLIENT_ID: &str = "satori-cli-83740771-1";
 pub(super) static EXPECTED_STATE: OnceLock<String> = OnceLock::new();
@@ -53,10 +53,10 @@ pub enum CredentialsFormat {
     Csv,
 }
 
-#[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
 pub struct Credentials {
     pub username: String,
-    pub password: String,
+    pub password: Secret,
     pub expires_at: DateTime<Utc>,
 }
 
@@ -96,16 +96,6 @@ impl Credentials {
     }
 }
 
-impl fmt::Debug for Credentials {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Credentials")
-            .field("username", &self.username)
-            .field("password", &"*********")
-            .field("expires_at", &self.expires_at)
-            .finish()
-    }
-}
-
 impl From<DefaultFolderError> for LoginBuilderError {
     fn from(value: DefaultFolderError) -> Self {
         LoginBuilderError::ValidationError(format!("Failed to get home folder error: {value:?}"))
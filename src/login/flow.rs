@@ -17,7 +17,7 @@ use crate::helpers::satori_console;
 use crate::login::data::{CODE_VERIFIER, EXPECTED_STATE, JWT};
 use crate::login::web_server;
 
-use super::data::{Credentials, CredentialsFormat, Login, CLIENT_ID};
+use super::data::{Credentials, CredentialsFormat, Jwt, Login, CLIENT_ID};
 use super::errors;
 
 const OAUTH_URI: &str = "oauth/authorize";
@@ -209,7 +209,7 @@ where
 async fn get_database_credentials_from_satori(
     user_id: &str,
     domain: &str,
-    jwt: &str,
+    jwt: &Jwt,
     invalid_cert: bool,
 ) -> Result<Credentials, errors::LoginError> {
     Ok(
@@ -224,7 +224,7 @@ async fn get_jwt<R>(
     open_browser: bool,
     invalid_cert: bool,
     user_input_stream: R,
-) -> Result<String, errors::LoginError>
+) -> Result<Jwt, errors::LoginError>
 where
     R: BufRead,
 {
@@ -257,7 +257,7 @@ async fn no_browser<R>(
     code_verifier: &str,
     invalid_cert: bool,
     user_input_stream: R,
-) -> Result<String, errors::LoginError>
+) -> Result<Jwt, errors::LoginError>
 where
     R: BufRead,
 {
@@ -296,7 +296,7 @@ fn with_browser(
     domain: &str,
     state: &str,
     code_challenge: &str,
-) -> Result<String, errors::LoginError> {
+) -> Result<Jwt, errors::LoginError> {
     CODE_VERIFIER.set(code_verifier).unwrap();
     let port = addr.port();
     let redirect_url = format!("http://localhost:{port}");
@@ -372,7 +372,9 @@ fn credentials_as_string(credentials: &Credentials, format: &CredentialsFormat)
     match format {
         CredentialsFormat::Csv => format!(
             "{},{},{}",
-            credentials.username, credentials.password, credentials.expires_at
+            credentials.username,
+            credentials.password.expose(),
+            credentials.expires_at
         ),
         CredentialsFormat::Json => serde_json::to_string(credentials).unwrap(),
         CredentialsFormat::Yaml => serde_yaml::to_string(credentials).unwrap(),
@@ -386,7 +388,16 @@ fn create_directories_for_file(file_path: &Path) -> io::Result<()> {
     Ok(())
 }
 
+/// Load the cached credentials without checking if they are expired
+pub fn load_cached_credentials(satori_folder_path: &Path) -> Option<Credentials> {
+    parse_credentials_file(&get_credentials_file_path(satori_folder_path))
+}
+
 fn read_credentials_from_file(file_path: &PathBuf) -> Option<Credentials> {
+    parse_credentials_file(file_path).filter(|credentials| !credentials.expires_soon())
+}
+
+fn parse_credentials_file(file_path: &PathBuf) -> Option<Credentials> {
     match fs::read_to_string(file_path) {
         Ok(cred_string) => {
             log::debug!("Successfully read file: {:?}", file_path);
@@ -395,7 +406,6 @@ fn read_credentials_from_file(file_path: &PathBuf) -> Option<Credentials> {
                     log::warn!("Failed to parse credentials: {}, generating new.", err);
                 })
                 .ok()
-                .filter(|credentials| !credentials.expires_soon())
         }
         Err(err) => {
             log::debug!("Failed to read file: {}", err);
@@ -404,7 +414,7 @@ fn read_credentials_from_file(file_path: &PathBuf) -> Option<Credentials> {
     }
 }
 
-fn wait_till_jwt() -> Result<String, errors::LoginError> {
+fn wait_till_jwt() -> Result<Jwt, errors::LoginError> {
     let start_time = Instant::now();
     loop {
         if let Some(jwt) = JWT.get() {
@@ -443,4 +453,10 @@ fn check_datastores_available(ds_info: &DatastoresInfo) {
         log::warn!("No datastores found, go to the Satori Data Portal and verify that you have access to the relevant dataset. Also ensure that you have satori authentication for the datastore"
     )
     }
+    if !ds_info.unusable_datastores.is_empty() {
+        log::warn!(
+            "{} datastores can't be used by the Satori CLI, run `satori list --all` to see why",
+            ds_info.unusable_datastores.len()
+        );
+    }
 }
@@ -2,8 +2,8 @@ use std::io;
 
 use anyhow::{anyhow, Result};
 use helpers::logger;
-use run::CommandExecuter;
-use satori_cli::{helpers, list, login, pwd, run, tools};
+use run::CommandStatus;
+use satori_cli::{env, helpers, list, login, pwd, run, status, tools, url};
 
 mod cli;
 
@@ -19,26 +19,29 @@ async fn main() {
     logger::init();
     log::debug!("running satori cli with parameters: {:?}", flow);
 
-    let exit_status = if let Err(err) = handle_flow(flow).await {
-        log::error!("{}", err);
-        1
-    } else {
-        0
+    let exit_status = match handle_flow(flow).await {
+        Ok(status) => status.exit_code(),
+        Err(err) => {
+            log::error!("{}", err);
+            1
+        }
     };
     std::process::exit(exit_status);
 }
 
-async fn handle_flow(flow: cli::Flow) -> Result<()> {
+/// The status of the command satori ran, or success for the other flows.
+async fn handle_flow(flow: cli::Flow) -> Result<CommandStatus> {
     let reader = io::stdin();
     let input = reader.lock();
-    let command_executer = CommandExecuter;
-    match flow {
+    let result = match flow {
         cli::Flow::Login(params) => login::run(&params, input)
             .await
             .map_err(|err| anyhow!("Failed to login: {}", err)),
-        cli::Flow::Run(params) => run::run(params, input, command_executer)
-            .await
-            .map_err(|err| anyhow!("Failed to run: {}", err)),
+        cli::Flow::Run(params, command_executer) => {
+            return run::run(params, input, command_executer)
+                .await
+                .map_err(|err| anyhow!("Failed to run: {}", err))
+        }
         cli::Flow::AutoComplete(params, out) => {
             cli::auto_complete(params, out);
             Ok(())
@@ -52,5 +55,24 @@ async fn handle_flow(flow: cli::Flow) -> Result<()> {
         cli::Flow::Pwd(params) => pwd::run(params, input)
             .await
             .map_err(|err| anyhow!("{}", err)),
-    }
+        cli::Flow::Status(params) => {
+            status::run(&params, &mut io::stdout());
+            Ok(())
+        }
+        cli::Flow::Url(params) => url::run(params, input, &mut io::stdout())
+            .await
+            .map_err(|err| anyhow!("{}", err)),
+        cli::Flow::Env(params) => env::run(params, input, &mut io::stdout())
+            .await
+            .map_err(|err| anyhow!("{}", err)),
+        cli::Flow::DryRun(flow, dry_run) => {
+            drop(input);
+            let status = Box::pin(handle_flow(*flow)).await?;
+            dry_run
+                .print(&mut io::stdout())
+                .map_err(|err| anyhow!("Failed to print the plan: {}", err))?;
+            return Ok(status);
+        }
+    };
+    result.map(|()| CommandStatus::SUCCESS)
 }
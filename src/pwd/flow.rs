@@ -8,6 +8,6 @@ where
     let (credentials, _datastores_info) = login::run_with_file(&params.login, user_input_stream)
         .await
         .map_err(|e| format!("{}", e))?;
-    println!("{}", credentials.password);
+    println!("{}", credentials.password.expose());
     Ok(())
 }
@@ -0,0 +1,234 @@
+use std::{
+    ffi::OsStr,
+    io,
+    process::{Child, Command, ExitStatus},
+};
+
+use super::{errors::RunError, CommandStatus, ExecuteCommand};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CommandExecuter {
+    /// Replace the satori process with the command, so an interactive client owns the terminal.
+    /// Unix only, on other platforms the command is waited for.
+    pub replace_process: bool,
+}
+
+impl ExecuteCommand for CommandExecuter {
+    fn execute<T, S, V, G, A>(
+        &self,
+        command_name: &str,
+        args: A,
+        env: T,
+    ) -> Result<CommandStatus, RunError>
+    where
+        T: IntoIterator<Item = (S, V)>,
+        A: IntoIterator<Item = G>,
+        G: AsRef<OsStr>,
+        S: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        let mut command = Command::new(command_name);
+        command.args(args);
+        command.envs(env);
+        if self.replace_process {
+            return replace_process(&mut command, command_name);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|err| RunError::CommandError(err, command_name.to_string()))?;
+        let status = wait_forwarding_signals(&mut child)
+            .map_err(|err| RunError::SpawnError(err, command_name.to_string()))?;
+        log::debug!("{command_name} exited with {status}");
+        Ok(CommandStatus::from(status))
+    }
+
+    fn replaces_process(&self) -> bool {
+        cfg!(unix) && self.replace_process
+    }
+}
+
+/// Only returns when the command couldn't be executed.
+#[cfg(unix)]
+fn replace_process(command: &mut Command, command_name: &str) -> Result<CommandStatus, RunError> {
+    use std::os::unix::process::CommandExt;
+
+    let err = command.exec();
+    Err(RunError::CommandError(err, command_name.to_string()))
+}
+
+#[cfg(not(unix))]
+fn replace_process(command: &mut Command, command_name: &str) -> Result<CommandStatus, RunError> {
+    log::warn!("--exec is only supported on Unix, waiting for {command_name} instead");
+    let status = command
+        .spawn()
+        .map_err(|err| RunError::CommandError(err, command_name.to_string()))?
+        .wait()
+        .map_err(|err| RunError::SpawnError(err, command_name.to_string()))?;
+    Ok(CommandStatus::from(status))
+}
+
+/// SIGTERM sent to satori is forwarded to the command, instead of killing satori and leaving the
+/// command running. SIGINT is ignored while waiting, the command is in the same process group and
+/// already gets Ctrl-C from the terminal, a second SIGINT makes tools such as dbt abort without
+/// cleaning up.
+#[cfg(unix)]
+fn wait_forwarding_signals(child: &mut Child) -> io::Result<ExitStatus> {
+    use nix::{sys::signal, unistd::Pid};
+    use signal_hook::{
+        consts::{SIGINT, SIGTERM},
+        iterator::Signals,
+    };
+
+    let mut signals = Signals::new([SIGINT, SIGTERM])?;
+    let handle = signals.handle();
+    let pid = Pid::from_raw(child.id() as i32);
+    let forwarder = std::thread::spawn(move || {
+        for signal in signals.forever() {
+            if signal == SIGINT {
+                log::debug!("Ignoring SIGINT, {pid} gets it from its process group");
+                continue;
+            }
+            log::debug!("Forwarding signal {signal} to {pid}");
+            if let Err(err) =
+                signal::Signal::try_from(signal).and_then(|sig| signal::kill(pid, sig))
+            {
+                log::debug!("Failed to forward signal {signal}: {err}");
+            }
+        }
+    });
+    let status = child.wait();
+    handle.close();
+    if forwarder.join().is_err() {
+        log::debug!("The signals forwarding thread panicked");
+    }
+    status
+}
+
+#[cfg(not(unix))]
+fn wait_forwarding_signals(child: &mut Child) -> io::Result<ExitStatus> {
+    child.wait()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn run_shell(script: &str) -> CommandStatus {
+        CommandExecuter::default()
+            .execute("sh", ["-c", script], Vec::<(String, String)>::new())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(run_shell("exit 3"), CommandStatus::Exited(3));
+        assert_eq!(run_shell("true").exit_code(), 0);
+    }
+
+    const SIGINT_COUNT_FILE: &str = "SATORI_TEST_SIGINT_COUNT_FILE";
+
+    /// Ctrl-C sends SIGINT to the whole foreground process group, the command must get it once.
+    #[test]
+    fn test_sigint_to_process_group_is_not_forwarded() {
+        use std::{fs, os::unix::process::CommandExt, process::Command, thread, time::Duration};
+
+        use nix::{
+            sys::signal::{killpg, Signal},
+            unistd::Pid,
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let count_file = temp_dir.path().join("count");
+        // This test binary in a process group of its own, standing for satori, runs the command
+        // which counts the signals, so the signal doesn't reach the test runner.
+        let mut satori = Command::new(std::env::current_exe().unwrap())
+            .args([
+                "--ignored",
+                "--exact",
+                "run::command_executer::tests::execute_sigint_counter",
+            ])
+            .env(SIGINT_COUNT_FILE, &count_file)
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        while !count_file.with_extension("ready").exists() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        killpg(Pid::from_raw(satori.id() as i32), Signal::SIGINT).unwrap();
+        while !count_file.with_extension("received").exists() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        fs::write(count_file.with_extension("done"), "").unwrap();
+
+        assert!(satori.wait().unwrap().success());
+        assert_eq!(fs::read_to_string(count_file).unwrap(), "1");
+    }
+
+    /// Runs [`count_sigint`] with the executer, run by
+    /// [`test_sigint_to_process_group_is_not_forwarded`].
+    #[test]
+    #[ignore]
+    fn execute_sigint_counter() {
+        let Some(count_file) = std::env::var_os(SIGINT_COUNT_FILE) else {
+            return;
+        };
+        let status = CommandExecuter::default()
+            .execute(
+                std::env::current_exe().unwrap().to_str().unwrap(),
+                [
+                    "--ignored",
+                    "--exact",
+                    "run::command_executer::tests::count_sigint",
+                ],
+                [(SIGINT_COUNT_FILE, count_file)],
+            )
+            .unwrap();
+        assert_eq!(status, CommandStatus::Exited(0));
+    }
+
+    /// Writes the number of SIGINT received until the `done` file is created, run by
+    /// [`execute_sigint_counter`].
+    #[test]
+    #[ignore]
+    fn count_sigint() {
+        use std::{
+            fs,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            thread,
+            time::Duration,
+        };
+
+        let Some(count_file) = std::env::var_os(SIGINT_COUNT_FILE) else {
+            return;
+        };
+        let count_file = std::path::PathBuf::from(count_file);
+        let count = Arc::new(AtomicUsize::new(0));
+        let handler_count = Arc::clone(&count);
+        // SAFETY: the handler only increments an atomic, which is async-signal-safe.
+        unsafe {
+            signal_hook::low_level::register(signal_hook::consts::SIGINT, move || {
+                handler_count.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+        fs::write(count_file.with_extension("ready"), "").unwrap();
+        while count.load(Ordering::SeqCst) == 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+        fs::write(count_file.with_extension("received"), "").unwrap();
+        while !count_file.with_extension("done").exists() {
+            thread::sleep(Duration::from_millis(10));
+        }
+        fs::write(count_file, count.load(Ordering::SeqCst).to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_killed_by_signal() {
+        let status = run_shell("kill -TERM $$");
+        assert_eq!(status, CommandStatus::Signaled(15));
+        assert_eq!(status.exit_code(), 143);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    helpers::tools::{self, CliArgType, Tool},
+    login,
+};
+
+use super::{
+    dynamic_tools::{self, ToolContext},
+    errors, CommandStatus, Connect, ExecuteCommand, ToolArg,
+};
+
+pub async fn run<R, C>(
+    params: Connect,
+    user_input_stream: R,
+    command_executer: C,
+) -> Result<CommandStatus, errors::RunError>
+where
+    R: std::io::BufRead,
+    C: ExecuteCommand,
+{
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+    let datastore_info =
+        datastores_info.resolve(&params.datastore_name, params.datastore_id.as_deref())?;
+    let tool_data = tools::get_default_tool(&datastore_info.r#type)?;
+    log::debug!(
+        "Connecting to {} with {}",
+        params.datastore_name,
+        tool_data.name
+    );
+    let tool_args = get_tool_args(tool_data, &params.database)?;
+
+    let tool_context = ToolContext {
+        datastore_name: &params.datastore_name,
+        datastore_info,
+        account_id: &datastores_info.account_id,
+        credentials: &credentials,
+        database: &params.database,
+        tool_args: &tool_args,
+    };
+    dynamic_tools::run_tool(
+        tool_data,
+        &tool_context,
+        &params.additional_args,
+        command_executer,
+    )
+}
+
+/// Only the database can be given to connect, the other args of the tool use their defaults.
+fn get_tool_args(
+    tool_data: &Tool,
+    database: &Option<String>,
+) -> Result<BTreeMap<String, ToolArg>, errors::RunError> {
+    let mut tool_args = BTreeMap::new();
+    for cli_arg in &tool_data.cli_args {
+        let value = match (cli_arg.r#type, database) {
+            (CliArgType::Flag, _) => ToolArg::Flag(false),
+            (_, Some(database)) if cli_arg.name == "database" => ToolArg::Value(database.clone()),
+            _ => match &cli_arg.default {
+                Some(default) => ToolArg::Value(default.clone()),
+                None if cli_arg.required => {
+                    return Err(errors::RunError::MissingToolArgument {
+                        tool: tool_data.name.clone(),
+                        argument: cli_arg.name.clone(),
+                    })
+                }
+                None => continue,
+            },
+        };
+        tool_args.insert(cli_arg.context_name(), value);
+    }
+    Ok(tool_args)
+}
@@ -1,7 +1,10 @@
 use derive_builder::Builder;
 
-use crate::login::Login;
-use std::{ffi::OsStr, path::PathBuf};
+use crate::{
+    helpers::{dry_run::DryRun, secret::Secret},
+    login::Login,
+};
+use std::{collections::BTreeMap, ffi::OsStr, path::PathBuf, process::ExitStatus};
 
 use super::errors::RunError;
 
@@ -9,6 +12,8 @@ use super::errors::RunError;
 pub enum Run {
     Dbt(Dbt),
     DynamicTool(DynamicTool),
+    Exec(Exec),
+    Connect(Connect),
 }
 
 #[derive(Debug, Builder)]
@@ -16,9 +21,18 @@ pub enum Run {
 pub struct Dbt {
     pub login: Login,
     pub target: Option<String>,
-    pub profile_name: String,
+    /// Overrides the profile of the dbt project.
+    #[builder(default)]
+    pub profile_name: Option<String>,
+    /// The directory of `dbt_project.yml`, when unset it is searched from the current
+    /// directory up, like dbt does.
+    #[builder(default)]
+    pub project_dir: Option<PathBuf>,
     pub profiles_path: PathBuf,
     pub additional_args: Vec<String>,
+    /// Records the rewrite of the profiles instead of writing them.
+    #[builder(default)]
+    pub dry_run: Option<DryRun>,
 }
 
 #[derive(Debug, Builder)]
@@ -27,40 +41,106 @@ pub struct DynamicTool {
     pub tool: String,
     pub login: Login,
     pub datastore_name: String,
+    /// Selects between datastores sharing the same name.
+    #[builder(default)]
+    pub datastore_id: Option<String>,
     pub additional_args: Vec<String>,
     pub database: Option<String>,
+    /// The values of the tool `cli_args`, by their jinja context name.
+    #[builder(default)]
+    pub args: BTreeMap<String, ToolArg>,
+}
+
+/// The value of a tool `cli_args` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolArg {
+    Value(String),
+    Flag(bool),
+}
+
+/// Connect to a datastore with the default tool of its type.
+#[derive(Debug, Builder)]
+#[builder(pattern = "owned")]
+pub struct Connect {
+    pub login: Login,
+    pub datastore_name: String,
+    /// Selects between datastores sharing the same name.
+    #[builder(default)]
+    pub datastore_id: Option<String>,
+    #[builder(default)]
+    pub database: Option<String>,
+    #[builder(default)]
+    pub additional_args: Vec<String>,
+}
+
+/// Run any command with the connection details of the datastore in its environment.
+#[derive(Debug, Builder)]
+#[builder(pattern = "owned")]
+pub struct Exec {
+    pub login: Login,
+    pub datastore_name: String,
+    /// Selects between datastores sharing the same name.
+    #[builder(default)]
+    pub datastore_id: Option<String>,
+    #[builder(default)]
+    pub database: Option<String>,
+    pub command: String,
+    #[builder(default)]
+    pub args: Vec<String>,
 }
 
 pub trait ExecuteCommand {
-    fn execute<T, S, V, G, A>(&self, command_name: &str, args: A, env: T) -> Result<(), RunError>
+    fn execute<T, S, V, G, A>(
+        &self,
+        command_name: &str,
+        args: A,
+        env: T,
+    ) -> Result<CommandStatus, RunError>
     where
         T: IntoIterator<Item = (S, V)>,
         A: IntoIterator<Item = G>,
         G: AsRef<OsStr>,
         S: AsRef<OsStr>,
         V: AsRef<OsStr>;
+
+    /// Whether the command replaces the current process, so nothing runs after it.
+    fn replaces_process(&self) -> bool {
+        false
+    }
+
+    /// Keeps the secret out of anything the executer reports, such as a dry run plan.
+    fn add_secret(&self, _secret: &Secret) {}
 }
 
-#[derive(Default)]
-pub struct CommandExecuter;
+/// How the command ended, satori exits with the same code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandStatus {
+    Exited(i32),
+    /// Killed by the signal, Unix only.
+    Signaled(i32),
+}
 
-impl ExecuteCommand for CommandExecuter {
-    fn execute<T, S, V, G, A>(&self, command_name: &str, args: A, env: T) -> Result<(), RunError>
-    where
-        T: IntoIterator<Item = (S, V)>,
-        A: IntoIterator<Item = G>,
-        G: AsRef<OsStr>,
-        S: AsRef<OsStr>,
-        V: AsRef<OsStr>,
-    {
-        let mut command = std::process::Command::new(command_name);
-        command.args(args);
-        command.envs(env);
-        command
-            .spawn()
-            .map_err(|err| RunError::CommandError(err, command_name.to_string()))?
-            .wait()
-            .map_err(|err| RunError::SpawnError(err, command_name.to_string()))?;
-        Ok(())
+impl CommandStatus {
+    pub const SUCCESS: Self = Self::Exited(0);
+
+    /// The exit code of the command, or 128 + signal like shells report it.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Exited(code) => code,
+            Self::Signaled(signal) => 128 + signal,
+        }
+    }
+}
+
+impl From<ExitStatus> for CommandStatus {
+    fn from(status: ExitStatus) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            if let Some(signal) = status.signal() {
+                return Self::Signaled(signal);
+            }
+        }
+        Self::Exited(status.code().unwrap_or(1))
     }
 }
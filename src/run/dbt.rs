@@ -1,123 +1,449 @@
-use core::fmt;
 use std::{
     collections::HashMap,
-    fs::{self, File},
-    path::PathBuf,
+    env,
+    fs::File,
+    path::{Path, PathBuf},
 };
 
-use regex::Regex;
+use minijinja::{Environment, Error, ErrorKind, Value};
+use similar::TextDiff;
+use tempfile::TempDir;
+use zeroize::Zeroizing;
 
-use crate::login;
+use crate::{
+    helpers::{
+        datastores::{errors::ResolveDatastoreError, DatastoreInfo, DatastoresInfo},
+        dry_run::FileChange,
+        satori_console::DatastoreType,
+        secret::Secret,
+    },
+    login::{self, data::Credentials},
+};
 
-use super::{errors, Dbt, ExecuteCommand};
+use super::{errors, CommandStatus, Dbt, ExecuteCommand};
 
 type ProfileName = String;
 type TargetName = String;
 
+const PROJECT_FILE_NAME: &str = "dbt_project.yml";
+const PROFILES_FILE_NAME: &str = "profiles.yml";
+/// dbt's target when the profile doesn't set one.
+const DEFAULT_TARGET: &str = "default";
+/// The environment variables of the credentials, dbt scrubs `DBT_ENV_SECRET_` values from its logs.
+pub(crate) const USERNAME_ENV: &str = "DBT_ENV_SECRET_SATORI_USERNAME";
+pub(crate) const PASSWORD_ENV: &str = "DBT_ENV_SECRET_SATORI_PASSWORD";
+
 pub async fn run<R, C>(
     params: Dbt,
     user_input_stream: R,
     command_executer: C,
-) -> Result<(), errors::RunError>
+) -> Result<CommandStatus, errors::RunError>
 where
     R: std::io::BufRead,
     C: ExecuteCommand,
 {
+    let profile_name = match &params.profile_name {
+        Some(profile_name) => profile_name.clone(),
+        None => get_project_profile(params.project_dir.as_deref())?,
+    };
     let mut profiles = get_profiles(&params.profiles_path)?;
-    let active_profile = profiles
+    let mut active_profile = profiles
         .value
-        .get_mut(&params.profile_name)
-        .ok_or_else(|| errors::RunError::DbtProfileNotFound(params.profile_name.clone()))?;
+        .remove(&profile_name)
+        .ok_or_else(|| errors::RunError::DbtProfileNotFound(profile_name.clone()))?;
     log::debug!("active profile: {:?}", active_profile);
-    let target = params
-        .target
-        .unwrap_or_else(|| active_profile.target.clone());
-    let target_params = get_target_values(active_profile, &target)?;
+    let target = match params.target {
+        Some(target) => target,
+        None => render(&active_profile.target)?,
+    };
+    let mut target_params = active_profile
+        .outputs
+        .remove(&target)
+        .ok_or_else(|| errors::RunError::DbtTargetNotFound(target.clone()))?;
     log::debug!("target params: {:?}", target_params);
-    let mut rewritten = false;
-    if should_rewrite_field(&target_params.user) {
-        log::debug!("rewriting user");
-        "{{ env_var('SATORI_USERNAME') }}".clone_into(&mut target_params.user);
-        rewritten = true;
-    }
-    if should_rewrite_field(&target_params.password) {
-        "{{ env_var('SATORI_PASSWORD') }}".clone_into(&mut target_params.password);
-        rewritten = true;
-    }
-    log::debug!("rewritten {:?}", rewritten);
-    let (credentials, _) = login::run_with_file(&params.login, user_input_stream).await?;
-
-    if rewritten {
-        let bk_path = params.profiles_path.with_file_name("profiles.bk");
-        fs::copy(&params.profiles_path, bk_path).map_err(|err| {
-            errors::RunError::DbtProfilesBackupError(params.profiles_path.clone(), err)
-        })?;
-
-        let mut file = File::create(&params.profiles_path).map_err(|err| {
-            errors::RunError::DbtProfilesReadError(params.profiles_path.clone(), err)
-        })?;
-        serde_yaml::to_writer(&mut file, &profiles).map_err(|err| {
-            errors::RunError::DbtProfilesWriteError(params.profiles_path.clone(), err)
-        })?;
-    }
+    let adapter = Adapter::from_type(&target_params.adapter).ok_or_else(|| {
+        errors::RunError::DbtUnsupportedAdapter {
+            target: target.clone(),
+            adapter: target_params.adapter.clone(),
+        }
+    })?;
+
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+    command_executer.add_secret(&credentials.password);
+
+    let datastore_info =
+        resolve_target_datastore(&target, adapter, &mut target_params, &datastores_info)?;
+    let envs = adapter.set_credentials(&mut target_params, datastore_info, &credentials);
+    let generated_profiles = Profiles {
+        value: HashMap::from([(
+            profile_name.clone(),
+            ProfileValues {
+                target: target.clone(),
+                outputs: HashMap::from([(target.clone(), target_params)]),
+            },
+        )]),
+    };
+
+    // The user's profiles file is never touched, dbt reads a generated copy of the target
+    // which lives until dbt exits.
+    let profiles_dir = if let Some(dry_run) = &params.dry_run {
+        let profiles_dir = PathBuf::from("<temporary profiles dir>");
+        dry_run.record_file(get_profiles_change(
+            &profiles_dir.join(PROFILES_FILE_NAME),
+            &generated_profiles,
+        )?);
+        ProfilesDir::DryRun(profiles_dir)
+    } else {
+        ProfilesDir::Temporary(write_profiles_dir(&generated_profiles)?)
+    };
 
-    let profiles_path = params
-        .profiles_path
-        .parent()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_owned();
     let mut args = params.additional_args;
+    if let Some(project_dir) = &params.project_dir {
+        args.extend([
+            "--project-dir".to_string(),
+            project_dir.to_string_lossy().into_owned(),
+        ]);
+    }
+    if params.profile_name.is_some() {
+        args.extend(["--profile".to_string(), profile_name]);
+    }
     args.extend([
         "--profiles-dir".to_string(),
-        profiles_path,
+        profiles_dir.path().to_string_lossy().into_owned(),
         "--target".to_string(),
         target,
     ]);
 
-    let envs = [
-        ("PGCHANNELBINDING", "disable".to_string()),
-        ("SATORI_USERNAME", credentials.username),
-        ("SATORI_PASSWORD", credentials.password),
-    ];
+    log::debug!(
+        "executing dbt with args: {:?} env: {:?}",
+        args,
+        envs.iter().map(|(name, _)| name).collect::<Vec<_>>()
+    );
 
-    log::debug!("executing dbt with args: {:?} env: {:?}", args, envs);
+    command_executer.execute("dbt", args, envs)
+}
 
-    command_executer.execute("dbt", args, envs)?;
-    Ok(())
+/// The dbt adapters, by the `type` of the target, which can connect through Satori.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Adapter {
+    Postgres,
+    Redshift,
+    Greenplum,
+    Athena,
 }
 
-fn get_profiles(profiles_path: &PathBuf) -> Result<Profiles, errors::RunError> {
-    let file = File::open(profiles_path)
-        .map_err(|err| errors::RunError::DbtProfilesReadError(profiles_path.clone(), err))?;
-    let buf = std::io::BufReader::new(file);
-    serde_yaml::from_reader::<_, Profiles>(buf)
-        .map_err(|err| errors::RunError::DbtProfilesParseError(profiles_path.clone(), err))
+impl Adapter {
+    fn from_type(adapter_type: &str) -> Option<Self> {
+        match adapter_type {
+            "postgres" => Some(Self::Postgres),
+            "redshift" => Some(Self::Redshift),
+            "greenplum" => Some(Self::Greenplum),
+            "athena" => Some(Self::Athena),
+            _ => None,
+        }
+    }
+
+    /// The adapter dbt connects to the datastore with.
+    pub(crate) fn for_datastore(datastore_type: &DatastoreType) -> Option<Self> {
+        match datastore_type {
+            DatastoreType::Postgresql | DatastoreType::CockroachDb => Some(Self::Postgres),
+            DatastoreType::Redshift => Some(Self::Redshift),
+            DatastoreType::Greenplum => Some(Self::Greenplum),
+            DatastoreType::Athena => Some(Self::Athena),
+            _ => None,
+        }
+    }
+
+    /// The `type` of the target.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Postgres => "postgres",
+            Self::Redshift => "redshift",
+            Self::Greenplum => "greenplum",
+            Self::Athena => "athena",
+        }
+    }
+
+    fn supports(self, datastore_type: &DatastoreType) -> bool {
+        match self {
+            Self::Postgres => datastore_type.is_postgres_dialect(),
+            Self::Redshift => datastore_type == &DatastoreType::Redshift,
+            Self::Greenplum => matches!(
+                datastore_type,
+                DatastoreType::Greenplum | DatastoreType::Postgresql
+            ),
+            Self::Athena => datastore_type == &DatastoreType::Athena,
+        }
+    }
+
+    /// Athena targets connect to an endpoint url instead of a host and port.
+    pub(crate) fn uses_host(self) -> bool {
+        self != Self::Athena
+    }
+
+    /// Points the target credentials to the dbt secret environment variables, and returns the
+    /// environment dbt runs with.
+    fn set_credentials(
+        self,
+        target_params: &mut TargetValues,
+        datastore_info: &DatastoreInfo,
+        credentials: &Credentials,
+    ) -> Vec<(&'static str, Zeroizing<String>)> {
+        let username = format!("{{{{ env_var('{USERNAME_ENV}') }}}}");
+        let password = format!("{{{{ env_var('{PASSWORD_ENV}') }}}}");
+        let mut envs = match self {
+            // libpq adapters, Satori doesn't support channel binding.
+            Self::Postgres | Self::Greenplum => {
+                vec![("PGCHANNELBINDING", "disable".to_string().into())]
+            }
+            Self::Redshift => {
+                // IAM authentication would ignore the user and password.
+                if target_params.extra_fields.contains_key("method") {
+                    target_params
+                        .extra_fields
+                        .insert("method".to_string(), "database".into());
+                }
+                vec![]
+            }
+            Self::Athena => {
+                for field in ["aws_profile_name", "aws_session_token"] {
+                    target_params.extra_fields.remove(field);
+                }
+                vec![(
+                    "AWS_ENDPOINT_URL",
+                    format!("https://{}", datastore_info.satori_host).into(),
+                )]
+            }
+        };
+        match self {
+            Self::Postgres | Self::Redshift | Self::Greenplum => {
+                // `pass` is the dbt alias of `password`.
+                target_params.extra_fields.remove("pass");
+                target_params.user = Some(username);
+                target_params.password = Some(Secret::new(password));
+            }
+            Self::Athena => {
+                target_params
+                    .extra_fields
+                    .insert("aws_access_key_id".to_string(), username.into());
+                target_params
+                    .extra_fields
+                    .insert("aws_secret_access_key".to_string(), password.into());
+            }
+        }
+        envs.extend([
+            (USERNAME_ENV, credentials.username.clone().into()),
+            // Zeroed once dbt is spawned.
+            (
+                PASSWORD_ENV,
+                credentials.password.expose().to_string().into(),
+            ),
+        ]);
+        envs
+    }
 }
 
-fn get_target_values<'a>(
-    profile: &'a mut ProfileValues,
+/// Only targets of Satori datastores get the Satori credentials. The target either points to the
+/// Satori hostname of the datastore, or names it with `satori_datastore`, or has no host and the
+/// user has a single datastore the adapter supports. The host and port of a named datastore are
+/// set in the target.
+fn resolve_target_datastore<'a>(
     target: &str,
-) -> Result<&'a mut TargetValues, errors::RunError> {
-    profile
-        .outputs
-        .get_mut(target)
-        .ok_or_else(|| errors::RunError::DbtTargetNotFound(target.to_string()))
+    adapter: Adapter,
+    target_params: &mut TargetValues,
+    datastores_info: &'a DatastoresInfo,
+) -> Result<&'a DatastoreInfo, errors::RunError> {
+    let datastore_info = if let Some(datastore_name) = target_params.satori_datastore.take() {
+        let datastore_name = render(&datastore_name)?;
+        log::debug!("target {target} uses the datastore {datastore_name}");
+        datastores_info.resolve(&datastore_name, None)?
+    } else if let Some(host) = &target_params.host {
+        let host = render(host)?;
+        let port = match target_params.extra_fields.get("port") {
+            Some(serde_json::Value::String(port)) => render(port)?.trim().parse::<u16>().ok(),
+            Some(port) => port.as_u64().and_then(|port| u16::try_from(port).ok()),
+            None => None,
+        };
+        let found = datastores_info
+            .find_by_host(&host, port)
+            .map_err(|err| match err {
+                ResolveDatastoreError::AmbiguousHost(host, datastores) => {
+                    errors::RunError::DbtTargetAmbiguousHost {
+                        target: target.to_string(),
+                        host,
+                        datastores,
+                    }
+                }
+                err => err.into(),
+            })?;
+        let Some((datastore_name, datastore_info)) = found else {
+            return Err(errors::RunError::DbtTargetNotSatori {
+                target: target.to_string(),
+                host,
+            });
+        };
+        log::debug!("target {target} host {host} is the datastore {datastore_name}");
+        check_datastore_type(target, adapter, datastore_info)?;
+        target_params.host = Some(host);
+        return Ok(datastore_info);
+    } else {
+        let mut supported = datastores_info
+            .datastores
+            .iter()
+            .filter(|(_, info)| adapter.supports(&info.r#type));
+        match (supported.next(), supported.next()) {
+            (Some((datastore_name, datastore_info)), None) => {
+                log::debug!("target {target} uses the only datastore {datastore_name}");
+                datastore_info
+            }
+            _ => {
+                return Err(errors::RunError::DbtTargetDatastoreRequired(
+                    target.to_string(),
+                ))
+            }
+        }
+    };
+    check_datastore_type(target, adapter, datastore_info)?;
+    if adapter.uses_host() {
+        target_params.host = Some(datastore_info.satori_host.clone());
+        if let Some(port) = datastore_info.port {
+            target_params
+                .extra_fields
+                .insert("port".to_string(), port.into());
+        }
+    }
+    Ok(datastore_info)
 }
 
-fn should_rewrite_field(field: &str) -> bool {
-    let re = Regex::new(r#"\{\{\s*env_var\(['"]([^'"]+)['"]\)\s*\}\}"#).unwrap();
-    if let Some(captures) = re.captures(field) {
-        log::debug!("Regex matched filed: {:?}", field);
-        let env_var = captures.get(1).unwrap().as_str();
-        // This case we are good, no need to do anything
-        !(env_var == "SATORI_USERNAME" || env_var == "SATORI_PASSWORD")
+fn check_datastore_type(
+    target: &str,
+    adapter: Adapter,
+    datastore_info: &DatastoreInfo,
+) -> Result<(), errors::RunError> {
+    if adapter.supports(&datastore_info.r#type) {
+        Ok(())
     } else {
-        true
+        Err(errors::RunError::DbtDatastoreTypeMismatch {
+            target: target.to_string(),
+            adapter: adapter.name().to_string(),
+            datastore_type: datastore_info.r#type.clone(),
+        })
     }
 }
 
+/// The profile of the dbt project. Like dbt, the project is read from `project_dir`, or else
+/// from the nearest directory holding `dbt_project.yml`, starting at the current directory.
+pub(crate) fn get_project_profile(project_dir: Option<&Path>) -> Result<String, errors::RunError> {
+    let project_dir = match project_dir {
+        Some(project_dir) => project_dir.to_path_buf(),
+        None => {
+            let current_dir = env::current_dir().map_err(|err| {
+                errors::RunError::DbtProjectReadError(PathBuf::from(PROJECT_FILE_NAME), err)
+            })?;
+            find_project_dir(&current_dir)
+                .ok_or(errors::RunError::DbtProjectNotFound(current_dir))?
+        }
+    };
+    let project_path = project_dir.join(PROJECT_FILE_NAME);
+    let file = File::open(&project_path)
+        .map_err(|err| errors::RunError::DbtProjectReadError(project_path.clone(), err))?;
+    let reader = std::io::BufReader::new(file);
+    let dbt_project = serde_yaml::from_reader::<_, DbtProject>(reader)
+        .map_err(|err| errors::RunError::DbtProjectParseError(project_path.clone(), err))?;
+    log::debug!("dbt project file: {:?}", project_path);
+    render(&dbt_project.profile)
+}
+
+fn find_project_dir(start_dir: &Path) -> Option<PathBuf> {
+    start_dir
+        .ancestors()
+        .find(|dir| dir.join(PROJECT_FILE_NAME).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Renders a value of the dbt project or profiles the way dbt does, with `env_var()` and the
+/// dbt type filters.
+fn render(value: &str) -> Result<String, errors::RunError> {
+    let mut env = Environment::new();
+    env.add_function("env_var", env_var);
+    for filter in ["as_text", "as_number", "as_bool", "as_native"] {
+        env.add_filter(filter, |value: Value| value);
+    }
+    env.render_str(value, ())
+        .map_err(|error| errors::RunError::DbtRenderError {
+            value: value.to_string(),
+            error,
+        })
+}
+
+/// `env_var(name, default)` fails when the variable isn't set and there is no default.
+fn env_var(name: &str, default: Option<Value>) -> Result<Value, Error> {
+    match (env::var(name), default) {
+        (Ok(value), _) => Ok(Value::from(value)),
+        (Err(_), Some(default)) => Ok(default),
+        (Err(_), None) => Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("Env var required but not provided: '{name}'"),
+        )),
+    }
+}
+
+enum ProfilesDir {
+    Temporary(TempDir),
+    DryRun(PathBuf),
+}
+
+impl ProfilesDir {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Temporary(dir) => dir.path(),
+            Self::DryRun(path) => path,
+        }
+    }
+}
+
+/// A private temporary directory holding the generated profiles, it is removed on drop.
+fn write_profiles_dir(profiles: &Profiles) -> Result<TempDir, errors::RunError> {
+    let profiles_dir = tempfile::Builder::new()
+        .prefix("satori-dbt-")
+        .tempdir()
+        .map_err(errors::RunError::DbtProfilesDirError)?;
+    let profiles_path = profiles_dir.path().join(PROFILES_FILE_NAME);
+    let file = File::create(&profiles_path).map_err(errors::RunError::DbtProfilesDirError)?;
+    serde_yaml::to_writer(file, profiles)
+        .map_err(|err| errors::RunError::DbtProfilesWriteError(profiles_path, err))?;
+    log::debug!("generated dbt profiles in {:?}", profiles_dir.path());
+    Ok(profiles_dir)
+}
+
+/// The content of the generated profiles file.
+fn get_profiles_change(
+    profiles_path: &Path,
+    profiles: &Profiles,
+) -> Result<FileChange, errors::RunError> {
+    let generated = serde_yaml::to_string(profiles)
+        .map_err(|err| errors::RunError::DbtProfilesWriteError(profiles_path.to_path_buf(), err))?;
+    let path = profiles_path.display().to_string();
+    let diff = TextDiff::from_lines("", &generated)
+        .unified_diff()
+        .header(&path, &path)
+        .to_string();
+    Ok(FileChange {
+        path: profiles_path.to_path_buf(),
+        diff: Some(diff),
+        ..Default::default()
+    })
+}
+
+fn get_profiles(profiles_path: &PathBuf) -> Result<Profiles, errors::RunError> {
+    let file = File::open(profiles_path)
+        .map_err(|err| errors::RunError::DbtProfilesReadError(profiles_path.clone(), err))?;
+    let buf = std::io::BufReader::new(file);
+    serde_yaml::from_reader::<_, Profiles>(buf)
+        .map_err(|err| errors::RunError::DbtProfilesParseError(profiles_path.clone(), err))
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Debug, Eq, PartialEq)]
 #[serde(transparent)]
 pub struct Profiles {
@@ -127,26 +453,34 @@ pub struct Profiles {
 #[derive(serde::Deserialize, serde::Serialize, Debug, Eq, PartialEq)]
 struct ProfileValues {
     // This is the default target if no target is specified
+    #[serde(default = "default_target")]
     target: String,
     outputs: HashMap<TargetName, TargetValues>,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Eq, PartialEq)]
+fn default_target() -> String {
+    DEFAULT_TARGET.to_string()
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct DbtProject {
+    profile: String,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Eq, PartialEq, Debug)]
 struct TargetValues {
-    host: String,
-    user: String,
-    password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<Secret>,
+    /// The name of a Satori datastore, replaced by its host and port.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    satori_datastore: Option<String>,
     #[serde(flatten)]
     extra_fields: serde_json::Map<String, serde_json::Value>,
-}
-
-impl fmt::Debug for TargetValues {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("TargetValues")
-            .field("host", &self.host)
-            .field("user", &self.user)
-            .field("password", &"********")
-            .field("extra_fields", &self.extra_fields)
-            .finish()
-    }
+    /// The dbt adapter of the target.
+    #[serde(rename = "type")]
+    adapter: String,
 }
@@ -0,0 +1,86 @@
+use std::ffi::OsStr;
+
+use crate::helpers::{dry_run::DryRun, secret::Secret};
+
+use super::{errors::RunError, CommandExecuter, CommandStatus, ExecuteCommand};
+
+/// Records the command in the plan instead of executing it.
+impl ExecuteCommand for DryRun {
+    fn execute<T, S, V, G, A>(
+        &self,
+        command_name: &str,
+        args: A,
+        env: T,
+    ) -> Result<CommandStatus, RunError>
+    where
+        T: IntoIterator<Item = (S, V)>,
+        A: IntoIterator<Item = G>,
+        G: AsRef<OsStr>,
+        S: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.record_command(
+            command_name,
+            args.into_iter()
+                .map(|arg| arg.as_ref().to_string_lossy().into_owned()),
+            env.into_iter().map(|(name, value)| {
+                (
+                    name.as_ref().to_string_lossy().into_owned(),
+                    value.as_ref().to_string_lossy().into_owned(),
+                )
+            }),
+        );
+        Ok(CommandStatus::SUCCESS)
+    }
+
+    fn add_secret(&self, secret: &Secret) {
+        DryRun::add_secret(self, secret);
+    }
+}
+
+/// The executer of the run flows, which either executes the command or only records it.
+#[derive(Debug, Clone)]
+pub enum Executer {
+    Command(CommandExecuter),
+    DryRun(DryRun),
+}
+
+impl Default for Executer {
+    fn default() -> Self {
+        Self::Command(CommandExecuter::default())
+    }
+}
+
+impl ExecuteCommand for Executer {
+    fn execute<T, S, V, G, A>(
+        &self,
+        command_name: &str,
+        args: A,
+        env: T,
+    ) -> Result<CommandStatus, RunError>
+    where
+        T: IntoIterator<Item = (S, V)>,
+        A: IntoIterator<Item = G>,
+        G: AsRef<OsStr>,
+        S: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        match self {
+            Self::Command(executer) => executer.execute(command_name, args, env),
+            Self::DryRun(dry_run) => dry_run.execute(command_name, args, env),
+        }
+    }
+
+    fn replaces_process(&self) -> bool {
+        match self {
+            Self::Command(executer) => executer.replaces_process(),
+            Self::DryRun(dry_run) => dry_run.replaces_process(),
+        }
+    }
+
+    fn add_secret(&self, secret: &Secret) {
+        if let Self::DryRun(dry_run) = self {
+            dry_run.add_secret(secret);
+        }
+    }
+}
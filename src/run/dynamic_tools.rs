@@ -1,99 +1,387 @@
-use minijinja::{context, Value};
+use std::{collections::BTreeMap, io::Write};
+
+use minijinja::{Environment, Value};
+use tempfile::NamedTempFile;
+use zeroize::Zeroizing;
 
 use crate::{
     helpers::{
         datastores::DatastoreInfo,
-        tools::{self, Tool},
+        tools::{self, template, CommandArgs, Tool},
     },
     login::{self, data::Credentials},
 };
 
-use super::{errors, DynamicTool, ExecuteCommand};
+use super::{errors, CommandStatus, DynamicTool, ExecuteCommand, ToolArg};
 
-const TOOLS_TEMPLATE_NAME: &str = "tools";
+/// Everything the tool templates are rendered with.
+pub(super) struct ToolContext<'a> {
+    pub datastore_name: &'a str,
+    pub datastore_info: &'a DatastoreInfo,
+    pub account_id: &'a str,
+    pub credentials: &'a Credentials,
+    pub database: &'a Option<String>,
+    pub tool_args: &'a BTreeMap<String, ToolArg>,
+}
 
 pub async fn run<R, C>(
     params: DynamicTool,
     user_input_stream: R,
     command_executer: C,
-) -> Result<(), errors::RunError>
+) -> Result<CommandStatus, errors::RunError>
 where
     R: std::io::BufRead,
     C: ExecuteCommand,
 {
+    let tool_data = tools::get_tool(&params.tool)?;
     let (credentials, datastores_info) =
         login::run_with_file(&params.login, user_input_stream).await?;
-    let datastore_info = datastores_info
-        .datastores
-        .get(&params.datastore_name)
-        .ok_or_else(|| errors::RunError::DatastoreNotFound(params.datastore_name.clone()))?;
-    let tool_data = get_tool_data(&params.tool);
-
-    let mut env = minijinja::Environment::new();
-    env.add_template(TOOLS_TEMPLATE_NAME, &tool_data.command_args)
-        .unwrap();
-    let args_string = get_args_from_env(&env, &params, datastore_info, &credentials)?;
+    let datastore_info =
+        datastores_info.resolve(&params.datastore_name, params.datastore_id.as_deref())?;
+
+    let tool_context = ToolContext {
+        datastore_name: &params.datastore_name,
+        datastore_info,
+        account_id: &datastores_info.account_id,
+        credentials: &credentials,
+        database: &params.database,
+        tool_args: &params.args,
+    };
+    run_tool(
+        tool_data,
+        &tool_context,
+        &params.additional_args,
+        command_executer,
+    )
+}
+
+/// Renders the tool templates with the datastore connection details and runs it.
+pub(super) fn run_tool<C>(
+    tool_data: &Tool,
+    tool_context: &ToolContext,
+    additional_args: &[String],
+    command_executer: C,
+) -> Result<CommandStatus, errors::RunError>
+where
+    C: ExecuteCommand,
+{
+    let datastore_info = tool_context.datastore_info;
+    if !tool_data.supports(&datastore_info.r#type) {
+        return Err(errors::RunError::UnsupportedDatastoreType {
+            tool: tool_data.name.clone(),
+            datastore_type: datastore_info.r#type.clone(),
+            supported_types: tool_data.supported_types.clone(),
+        });
+    }
 
-    let args = build_args(&args_string, &params);
+    command_executer.add_secret(&tool_context.credentials.password);
+    if !tool_data.files.is_empty() && command_executer.replaces_process() {
+        return Err(errors::RunError::ToolFilesWithExec(tool_data.name.clone()));
+    }
+    for template in tool_data.get_args_exposing_password() {
+        log::warn!(
+            "Tool {} puts the password on the command line with `{template}`, other users of the host can see it. Use env or files instead",
+            tool_data.name
+        );
+    }
 
-    let ctx = get_jinja_context(datastore_info, &credentials, &params)?;
+    let env = get_environment(tool_context);
+    let mut ctx = get_jinja_context(tool_context)?;
+    // Removed when dropped, after the tool exits
+    let files = write_files(&env, tool_data, &Value::from(ctx.clone()))?;
+    ctx.insert(
+        "files".to_string(),
+        files
+            .iter()
+            .map(|(name, file)| (name.clone(), file.path().display().to_string()))
+            .collect::<BTreeMap<String, String>>()
+            .into(),
+    );
+    let ctx = Value::from(ctx);
+    let args = build_args(&env, tool_data, &ctx, additional_args)?;
+
+    // The values usually hold the password, they are zeroed once the tool is spawned.
     let envs = tool_data
         .get_env()
         .iter()
         .map(|(name, value)| {
-            env.add_template(name, value).unwrap();
-            let tmpl = env.get_template(name).unwrap();
-            let env_string = tmpl.render(ctx.clone()).unwrap();
-            (name.clone(), env_string)
+            let env_string = render(&env, tool_data, &format!("env.{name}"), value, &ctx)?;
+            Ok((name.clone(), Zeroizing::new(env_string)))
         })
-        .collect::<Vec<(String, String)>>();
+        .collect::<Result<Vec<(String, Zeroizing<String>)>, errors::RunError>>()?;
 
-    command_executer.execute(&tool_data.command, args, envs)?;
-    Ok(())
+    command_executer.execute(&tool_data.command, args, envs)
 }
-/// Get the data of the tool from the tools.yaml file
-fn get_tool_data(tool_name: &str) -> Tool {
-    let tools_inventory = tools::get_or_init();
-    let tool_data = tools_inventory
-        .value
+
+fn get_environment(tool_context: &ToolContext) -> Environment<'static> {
+    let mut env = template::get_environment();
+    template::add_url_functions(
+        &mut env,
+        tool_context.datastore_info,
+        &tool_context.credentials.username,
+        &tool_context.credentials.password,
+        tool_context.database.as_deref(),
+    );
+    env
+}
+
+/// Writes the tool files, readable only by the user, by their name.
+fn write_files(
+    env: &Environment,
+    tool_data: &Tool,
+    ctx: &Value,
+) -> Result<Vec<(String, NamedTempFile)>, errors::RunError> {
+    tool_data
+        .files
         .iter()
-        .find(|tool| tool.name == tool_name)
-        .expect("Tool name wasn't found");
-    tool_data.clone()
+        .map(|tool_file| {
+            let content = Zeroizing::new(render(
+                env,
+                tool_data,
+                &format!("files.{}", tool_file.name),
+                &tool_file.content,
+                ctx,
+            )?);
+            let file_error = |err| {
+                errors::RunError::ToolFileError(tool_data.name.clone(), tool_file.name.clone(), err)
+            };
+            let mut file = tempfile::Builder::new()
+                .prefix("satori-")
+                .suffix(&tool_file.suffix)
+                .tempfile()
+                .map_err(file_error)?;
+            file.write_all(content.as_bytes()).map_err(file_error)?;
+            file.flush().map_err(file_error)?;
+            Ok((tool_file.name.clone(), file))
+        })
+        .collect()
 }
 
-fn get_args_from_env(
-    env: &minijinja::Environment<'_>,
-    params: &DynamicTool,
-    datastore_info: &DatastoreInfo,
-    credentials: &Credentials,
-) -> Result<String, errors::RunError> {
-    let tmpl = env.get_template(TOOLS_TEMPLATE_NAME).unwrap();
-    let ctx = get_jinja_context(datastore_info, credentials, params)?;
-    Ok(tmpl.render(ctx).expect("Failed to render tools template"))
+/// Render the tool arguments, followed by the arguments the user passed.
+fn build_args(
+    env: &Environment,
+    tool_data: &Tool,
+    ctx: &Value,
+    additional_args: &[String],
+) -> Result<Vec<String>, errors::RunError> {
+    let mut args = match &tool_data.command_args {
+        CommandArgs::Line(line) => render(env, tool_data, "command_args", line, ctx)?
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<String>>(),
+        CommandArgs::List(command_args) => {
+            let mut args = vec![];
+            for (index, command_arg) in command_args.iter().enumerate() {
+                let rendered = command_arg
+                    .get_templates(index)
+                    .into_iter()
+                    .map(|(name, template)| render(env, tool_data, &name, template, ctx))
+                    .collect::<Result<Vec<String>, errors::RunError>>()?;
+                // A flag is dropped with its value.
+                if rendered.iter().all(|arg| !arg.is_empty()) {
+                    args.extend(rendered);
+                }
+            }
+            args
+        }
+    };
+    args.extend(additional_args.iter().cloned());
+    Ok(args)
 }
 
-fn build_args<'a>(args_string: &'a str, params: &'a DynamicTool) -> Vec<&'a str> {
-    let mut args = args_string.split_whitespace().collect::<Vec<&str>>();
-    args.extend(
-        params
-            .additional_args
-            .iter()
-            .map(std::string::String::as_str),
-    );
-    args
+/// The template name locates the template in the tool definition in case of an error.
+fn render(
+    env: &Environment,
+    tool_data: &Tool,
+    template_name: &str,
+    template: &str,
+    ctx: &Value,
+) -> Result<String, errors::RunError> {
+    env.render_named_str(template_name, template, ctx)
+        .map_err(|error| errors::RunError::ToolTemplateError {
+            tool: tool_data.name.clone(),
+            template: template.to_string(),
+            error,
+        })
 }
 
+/// The tool args, and the connection details which take precedence over them.
 fn get_jinja_context(
-    datastore_info: &DatastoreInfo,
-    credentials: &Credentials,
-    params: &DynamicTool,
-) -> Result<Value, errors::RunError> {
-    Ok(context! {
-        host => datastore_info.get_datastore_name()?,
-        user => credentials.username,
-        password => credentials.password,
-        database => params.database,
-        port => datastore_info.port
-    })
+    tool_context: &ToolContext,
+) -> Result<BTreeMap<String, Value>, errors::RunError> {
+    let datastore_info = tool_context.datastore_info;
+    let credentials = tool_context.credentials;
+    let mut ctx = tool_context
+        .tool_args
+        .iter()
+        .map(|(name, arg)| {
+            let value = match arg {
+                ToolArg::Value(value) => Value::from(value.clone()),
+                ToolArg::Flag(flag) => Value::from(*flag),
+            };
+            (name.clone(), value)
+        })
+        .collect::<BTreeMap<String, Value>>();
+    ctx.extend([
+        (
+            "host".to_string(),
+            Value::from(datastore_info.get_datastore_name()?),
+        ),
+        (
+            "user".to_string(),
+            Value::from(credentials.username.clone()),
+        ),
+        (
+            "password".to_string(),
+            template::secret_value(&credentials.password),
+        ),
+        (
+            "database".to_string(),
+            Value::from(tool_context.database.clone()),
+        ),
+        ("port".to_string(), Value::from(datastore_info.port)),
+        (
+            "datastore_name".to_string(),
+            Value::from(tool_context.datastore_name),
+        ),
+        (
+            "datastore_id".to_string(),
+            Value::from(datastore_info.id.clone()),
+        ),
+        (
+            "datastore_type".to_string(),
+            Value::from_serialize(&datastore_info.r#type),
+        ),
+        (
+            "satori_host".to_string(),
+            Value::from(datastore_info.satori_host.clone()),
+        ),
+        (
+            "databases".to_string(),
+            Value::from_serialize(&datastore_info.databases),
+        ),
+        (
+            "deployment_type".to_string(),
+            Value::from_serialize(&datastore_info.deployment_type),
+        ),
+        (
+            "account_id".to_string(),
+            Value::from(tool_context.account_id),
+        ),
+        (
+            "expires_at".to_string(),
+            Value::from(credentials.expires_at.to_rfc3339()),
+        ),
+    ]);
+    Ok(ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::helpers::satori_console::DatastoreType;
+
+    use super::*;
+
+    fn datastore_info(port: Option<u16>) -> DatastoreInfo {
+        DatastoreInfo {
+            id: "id".to_string(),
+            satori_host: "satori.example.com".to_string(),
+            databases: vec![],
+            port,
+            r#type: DatastoreType::Postgresql,
+            deployment_type: None,
+        }
+    }
+
+    fn credentials() -> Credentials {
+        Credentials {
+            username: "user".to_string(),
+            password: "password".into(),
+            expires_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_tool_args_in_context() {
+        let tool = serde_yaml::from_str::<Tool>(
+            "name: mongosh
+command: mongosh
+command_args:
+  - '{{ host }}'
+  - '--readPreference={{ read_preference }}'
+  - '{% if quiet %}--quiet{% endif %}'
+  - '{{ role }}'
+  - '{{ datastore_name }} {{ datastore_type }} {{ account_id }}'
+  - '{{ jdbc_url() }}'
+",
+        )
+        .unwrap();
+        let datastore_info = datastore_info(Some(5432));
+        let credentials = credentials();
+        let args = BTreeMap::from([
+            (
+                "read_preference".to_string(),
+                ToolArg::Value("secondary".to_string()),
+            ),
+            ("quiet".to_string(), ToolArg::Flag(true)),
+            ("host".to_string(), ToolArg::Value("other".to_string())),
+        ]);
+        let tool_context = ToolContext {
+            datastore_name: "prod",
+            datastore_info: &datastore_info,
+            account_id: "account",
+            credentials: &credentials,
+            database: &Some("orders".to_string()),
+            tool_args: &args,
+        };
+        let env = get_environment(&tool_context);
+        let ctx = Value::from(get_jinja_context(&tool_context).unwrap());
+
+        let args = build_args(&env, &tool, &ctx, &["--eval".to_string()]).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "satori.example.com",
+                "--readPreference=secondary",
+                "--quiet",
+                "prod POSTGRESQL account",
+                "jdbc:postgresql://satori.example.com:5432/orders?user=user&password=password&sslmode=require",
+                "--eval"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_optional_values() {
+        let tool = serde_yaml::from_str::<Tool>(
+            "name: psql
+command: psql
+command_args:
+  - -h
+  - '{{ host }}'
+  - [-d, '{{ database }}']
+  - [--port, '{{ port }}']
+  - '{{ mode }}'
+",
+        )
+        .unwrap();
+        let datastore_info = datastore_info(None);
+        let credentials = credentials();
+        let args = BTreeMap::from([("mode".to_string(), ToolArg::Value("none".to_string()))]);
+        let tool_context = ToolContext {
+            datastore_name: "prod",
+            datastore_info: &datastore_info,
+            account_id: "account",
+            credentials: &credentials,
+            database: &None,
+            tool_args: &args,
+        };
+        let env = get_environment(&tool_context);
+        let ctx = Value::from(get_jinja_context(&tool_context).unwrap());
+
+        let args = build_args(&env, &tool, &ctx, &[]).unwrap();
+        assert_eq!(args, vec!["-h", "satori.example.com", "none"]);
+    }
 }
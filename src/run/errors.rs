@@ -3,7 +3,13 @@ use std::path::PathBuf;
 use thiserror::Error;
 
 use crate::{
-    helpers::{datastores::errors::GetHostError, default_app_folder::DefaultFolderError},
+    helpers::{
+        connection_url::ConnectionUrlError,
+        datastores::errors::{GetHostError, ResolveDatastoreError},
+        default_app_folder::DefaultFolderError,
+        satori_console::DatastoreType,
+        tools::errors::ToolsDataError,
+    },
     login::errors::LoginError,
 };
 
@@ -17,8 +23,19 @@ pub enum RunError {
     SpawnError(std::io::Error, String),
     #[error("{0}")]
     HomeFolderError(#[from] DefaultFolderError),
-    #[error("Failed to get datastore: {0} from datastores info file")]
-    DatastoreNotFound(String),
+    #[error("{0}")]
+    DatastoreResolveError(#[from] ResolveDatastoreError),
+    #[error("dbt_project.yml not found in {0} or any of its parent directories")]
+    DbtProjectNotFound(PathBuf),
+    #[error("Failed to read dbt project file {0}: {1}")]
+    DbtProjectReadError(PathBuf, std::io::Error),
+    #[error("Failed to parse dbt project file {0}: {1}")]
+    DbtProjectParseError(PathBuf, serde_yaml::Error),
+    #[error("Failed to render dbt value `{value}`: {error}")]
+    DbtRenderError {
+        value: String,
+        error: minijinja::Error,
+    },
     #[error("Failed to read dbt profiles file {0}: {1}")]
     DbtProfilesReadError(PathBuf, std::io::Error),
     #[error("Failed to parse dbt profiles file {0}: {1}")]
@@ -29,8 +46,48 @@ pub enum RunError {
     DbtProfileNotFound(String),
     #[error("DBT target {0} not found in profile")]
     DbtTargetNotFound(String),
-    #[error("Failed to backup dbt profiles file {0}: {1}")]
-    DbtProfilesBackupError(PathBuf, std::io::Error),
+    #[error("DBT target {target} host `{host}` is not a Satori datastore, use the Satori hostname of the datastore or set `satori_datastore: <datastore name>` in the target")]
+    DbtTargetNotSatori { target: String, host: String },
+    #[error("DBT target {target} host `{host}` serves more than one datastore, set its port or `satori_datastore` to one of: {datastores}")]
+    DbtTargetAmbiguousHost {
+        target: String,
+        host: String,
+        datastores: String,
+    },
+    #[error("DBT target {target} uses the {adapter} adapter, supported adapters are postgres, redshift, greenplum and athena")]
+    DbtUnsupportedAdapter { target: String, adapter: String },
+    #[error("DBT target {target} uses the {adapter} adapter, which can't connect to a {datastore_type:?} datastore")]
+    DbtDatastoreTypeMismatch {
+        target: String,
+        adapter: String,
+        datastore_type: DatastoreType,
+    },
+    #[error("DBT target {0} has no host, set `satori_datastore: <datastore name>` in the target")]
+    DbtTargetDatastoreRequired(String),
+    #[error("Failed to create the temporary dbt profiles directory: {0}")]
+    DbtProfilesDirError(std::io::Error),
     #[error("{0}")]
     GetHostError(#[from] GetHostError),
+    #[error("{0}")]
+    ConnectionUrlError(#[from] ConnectionUrlError),
+    #[error("{0}")]
+    ToolsDataError(#[from] ToolsDataError),
+    #[error("Tool {tool} doesn't support {datastore_type:?} datastores, it supports: {supported_types:?}")]
+    UnsupportedDatastoreType {
+        tool: String,
+        datastore_type: DatastoreType,
+        supported_types: Vec<DatastoreType>,
+    },
+    #[error("Tool {tool} requires the {argument} argument")]
+    MissingToolArgument { tool: String, argument: String },
+    #[error("Failed to write file {1} of tool {0}: {2}")]
+    ToolFileError(String, String, std::io::Error),
+    #[error("Tool {0} writes files which are removed when it exits, it can't be used with --exec")]
+    ToolFilesWithExec(String),
+    #[error("Failed to render `{template}` of tool {tool}: {error}")]
+    ToolTemplateError {
+        tool: String,
+        template: String,
+        error: minijinja::Error,
+    },
 }
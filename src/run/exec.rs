@@ -0,0 +1,37 @@
+use crate::{
+    helpers::{connection_env, connection_url::ConnectionDetails},
+    login,
+};
+
+use super::{errors, CommandStatus, Exec, ExecuteCommand};
+
+pub async fn run<R, C>(
+    params: Exec,
+    user_input_stream: R,
+    command_executer: C,
+) -> Result<CommandStatus, errors::RunError>
+where
+    R: std::io::BufRead,
+    C: ExecuteCommand,
+{
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+    command_executer.add_secret(&credentials.password);
+    let datastore_info =
+        datastores_info.resolve(&params.datastore_name, params.datastore_id.as_deref())?;
+    let connection_details = ConnectionDetails {
+        datastore_info,
+        username: &credentials.username,
+        password: &credentials.password,
+        database: params.database.as_deref(),
+        redact: false,
+    };
+    let envs = connection_env::get(&connection_details)?;
+    log::debug!(
+        "Running {} with the environment variables: {:?}",
+        params.command,
+        envs.iter().map(|(name, _)| name).collect::<Vec<_>>()
+    );
+
+    command_executer.execute(&params.command, &params.args, envs)
+}
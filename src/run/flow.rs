@@ -1,10 +1,10 @@
-use super::{dbt, dynamic_tools, errors, Run};
+use super::{connect, dbt, dynamic_tools, errors, exec, CommandStatus, Run};
 
 pub async fn run<R, C>(
     params: Run,
     user_input_stream: R,
     command_executer: C,
-) -> Result<(), errors::RunError>
+) -> Result<CommandStatus, errors::RunError>
 where
     R: std::io::BufRead,
     C: super::ExecuteCommand,
@@ -14,5 +14,7 @@ where
         Run::DynamicTool(params) => {
             dynamic_tools::run(params, user_input_stream, command_executer).await
         }
+        Run::Exec(params) => exec::run(params, user_input_stream, command_executer).await,
+        Run::Connect(params) => connect::run(params, user_input_stream, command_executer).await,
     }
 }
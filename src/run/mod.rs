@@ -1,9 +1,15 @@
+mod command_executer;
+pub mod connect;
 mod data;
 pub mod dbt;
+mod dry_run;
 pub mod dynamic_tools;
+pub mod exec;
 mod flow;
 
 pub mod errors;
 
+pub use command_executer::CommandExecuter;
 pub use data::*;
+pub use dry_run::Executer;
 pub use flow::run;
@@ -0,0 +1,6 @@
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct Status {
+    pub satori_folder_path: PathBuf,
+}
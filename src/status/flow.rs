@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use chrono::Utc;
+
+use crate::{helpers::datastores, login};
+
+use super::Status;
+
+/// Print the state of the local cache, credentials and datastores
+pub fn run<W>(params: &Status, writer: &mut W)
+where
+    W: std::io::Write,
+{
+    write_credentials_status(&params.satori_folder_path, writer);
+    write_datastores_status(&params.satori_folder_path, writer);
+}
+
+fn write_credentials_status<W>(path: &Path, writer: &mut W)
+where
+    W: std::io::Write,
+{
+    let status = match login::flow::load_cached_credentials(path) {
+        Some(credentials) if credentials.expires_at <= Utc::now() => {
+            format!("expired at {}", credentials.expires_at)
+        }
+        Some(credentials) => format!(
+            "{} valid until {}",
+            credentials.username, credentials.expires_at
+        ),
+        None => "not found, run `satori login`".to_string(),
+    };
+    writeln!(writer, "Credentials: {status}").expect("Failed to write");
+}
+
+fn write_datastores_status<W>(path: &Path, writer: &mut W)
+where
+    W: std::io::Write,
+{
+    let info = match datastores::file::load(path) {
+        Ok(info) => info,
+        Err(err) => {
+            log::debug!("Failed to load datastores: {err}");
+            writeln!(writer, "Datastores: not found, run `satori login`").expect("Failed to write");
+            return;
+        }
+    };
+    writeln!(
+        writer,
+        "Datastores: {} available, {} unusable",
+        info.datastores.len(),
+        info.unusable_datastores.len()
+    )
+    .expect("Failed to write");
+    if info.unusable_datastores.is_empty() {
+        return;
+    }
+    let mut unusable_datastores = info
+        .unusable_datastores
+        .iter()
+        .map(|(name, unusable)| format!("    {name}: {unusable}"))
+        .collect::<Vec<String>>();
+    unusable_datastores.sort();
+    writeln!(writer, "Unusable datastores:").expect("Failed to write");
+    writeln!(writer, "{}", unusable_datastores.join("\n")).expect("Failed to write");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_status() {
+        let mut buffer = Vec::new();
+        let params = Status {
+            satori_folder_path: Path::new("src/status/tests_files/").to_path_buf(),
+        };
+
+        run(&params, &mut buffer);
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output,
+            "Credentials: not found, run `satori login`\n\
+            Datastores: 1 available, 1 unusable\n\
+            Unusable datastores:\n    snowflakeName: Snowflake datastores are not supported by the Satori CLI\n"
+        );
+    }
+}
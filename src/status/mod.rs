@@ -0,0 +1,5 @@
+pub mod data;
+pub mod flow;
+
+pub use data::Status;
+pub use flow::run;
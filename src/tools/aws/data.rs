@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use derive_builder::Builder;
 
-use crate::login::Login;
+use crate::{helpers::dry_run::DryRun, login::Login};
 
 #[derive(Debug, Builder)]
 #[builder(pattern = "owned")]
@@ -10,4 +10,18 @@ pub struct Aws {
     pub login: Login,
     pub credentials_path: PathBuf,
     pub config_path: PathBuf,
+    /// Writes a `credential_process` running `satori aws credential-process` to the config file
+    /// instead of writing the credentials.
+    #[builder(default)]
+    pub credential_process: bool,
+    /// Records the changes to the files instead of writing them.
+    #[builder(default)]
+    pub dry_run: Option<DryRun>,
+}
+
+#[derive(Debug, Builder)]
+#[builder(pattern = "owned")]
+pub struct AwsCredentialProcess {
+    pub login: Login,
+    pub datastore_name: String,
 }
@@ -1,22 +1,29 @@
 use std::{
     collections::{hash_map::DefaultHasher, HashSet},
+    env,
     hash::{Hash, Hasher},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use ini::Ini;
+use chrono::SecondsFormat;
+use ini::{EscapePolicy, Ini, ParseOption};
+use serde::Serialize;
 
 use crate::{
-    helpers::datastores::{self, DatastoreInfo},
+    helpers::{
+        datastores::{self, DatastoreInfo},
+        dry_run::FileChange,
+    },
     login,
     tools::errors,
 };
 
-use super::Aws;
+use super::{Aws, AwsCredentialProcess};
 
 const PROFILE_NAME_PREFIX: &str = "satori";
 const AWS_KEY_NAME: &str = "aws_access_key_id";
 const AWS_SECRET_NAME: &str = "aws_secret_access_key";
+const CREDENTIAL_PROCESS_NAME: &str = "credential_process";
 
 pub async fn run<R>(params: Aws, user_input_stream: R) -> Result<(), errors::ToolsError>
 where
@@ -28,6 +35,18 @@ where
     let (credentials, datastores_info) =
         login::run_with_file(&params.login, user_input_stream).await?;
 
+    // The processes of the AWS SDKs don't always have satori in their `PATH`.
+    let satori_path = if params.credential_process {
+        env::current_exe()
+            .map_err(errors::ToolsError::SatoriPathError)?
+            .to_string_lossy()
+            .into_owned()
+    } else {
+        String::new()
+    };
+
+    let existing_credentials_sections = get_sections(&credentials_content);
+    let existing_config_sections = get_sections(&config_content);
     let mut expected_satori_profiles = HashSet::new();
 
     let mut is_first = true;
@@ -42,14 +61,27 @@ where
 
         expected_satori_profiles.insert(profile_name.clone());
 
+        let config_section = format!("profile {profile_name}");
         config_content
-            .with_section(Some(format!("profile {profile_name}")))
+            .with_section(Some(config_section.as_str()))
             .set("endpoint_url", format!("https://{endpoint_url}"));
 
-        credentials_content
-            .with_section(Some(profile_name.clone()))
-            .set(AWS_KEY_NAME, credentials.username.clone())
-            .set(AWS_SECRET_NAME, credentials.password.clone());
+        if params.credential_process {
+            config_content
+                .with_section(Some(config_section.as_str()))
+                .set(
+                    CREDENTIAL_PROCESS_NAME,
+                    get_credential_process_command(&satori_path, datastore_name),
+                );
+        } else {
+            config_content
+                .with_section(Some(config_section.as_str()))
+                .delete(&CREDENTIAL_PROCESS_NAME);
+            credentials_content
+                .with_section(Some(profile_name.clone()))
+                .set(AWS_KEY_NAME, credentials.username.clone())
+                .set(AWS_SECRET_NAME, credentials.password.expose());
+        }
         if is_first {
             log::info!("The following profiles have been generated:");
             is_first = false;
@@ -57,21 +89,118 @@ where
         log::info!("    {datastore_name}: {profile_name}");
     }
 
-    remove_stale_satori_profiles(&mut credentials_content, &expected_satori_profiles);
-    remove_stale_satori_profiles_from_config(&mut config_content, &expected_satori_profiles);
+    // The static keys of the profiles would take precedence over their credential process.
+    let expected_credentials_profiles = if params.credential_process {
+        HashSet::new()
+    } else {
+        expected_satori_profiles.clone()
+    };
+    let removed_credentials =
+        remove_stale_satori_profiles(&mut credentials_content, &expected_credentials_profiles);
+    let removed_config =
+        remove_stale_satori_profiles_from_config(&mut config_content, &expected_satori_profiles);
+
+    if let Some(dry_run) = &params.dry_run {
+        dry_run.record_file(get_change(
+            params.credentials_path,
+            &existing_credentials_sections,
+            expected_credentials_profiles.into_iter(),
+            removed_credentials,
+        ));
+        dry_run.record_file(get_change(
+            params.config_path,
+            &existing_config_sections,
+            expected_satori_profiles
+                .iter()
+                .map(|profile_name| format!("profile {profile_name}")),
+            removed_config,
+        ));
+        return Ok(());
+    }
 
+    // The AWS CLI and SDKs read the values as they are, without escapes.
     credentials_content
-        .write_to_file(params.credentials_path.clone())
+        .write_to_file_policy(params.credentials_path.clone(), EscapePolicy::Nothing)
         .map_err(|err| errors::ToolsError::FailedToWriteToFile(err, params.credentials_path))?;
     config_content
-        .write_to_file(params.config_path.clone())
+        .write_to_file_policy(params.config_path.clone(), EscapePolicy::Nothing)
         .map_err(|err| errors::ToolsError::FailedToWriteToFile(err, params.config_path))?;
 
     Ok(())
 }
 
+/// Prints the satori credentials in the format of the AWS `credential_process`, after checking
+/// that the datastore is an AWS datastore.
+pub async fn credential_process<R, W>(
+    params: &AwsCredentialProcess,
+    user_input_stream: R,
+    writer: &mut W,
+) -> Result<(), errors::ToolsError>
+where
+    R: std::io::BufRead,
+    W: std::io::Write,
+{
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+    let datastore_info = datastores_info.resolve(&params.datastore_name, None)?;
+    if !datastore_info.r#type.is_aws() {
+        return Err(errors::ToolsError::AwsUnsupportedDatastore(
+            params.datastore_name.clone(),
+            datastore_info.r#type.clone(),
+        ));
+    }
+    let process_credentials = ProcessCredentials {
+        version: 1,
+        access_key_id: &credentials.username,
+        secret_access_key: credentials.password.expose(),
+        expiration: credentials
+            .expires_at
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+    serde_json::to_writer(&mut *writer, &process_credentials)
+        .map_err(|err| errors::ToolsError::WriteError(err.into()))?;
+    writeln!(writer).map_err(errors::ToolsError::WriteError)
+}
+
+/// The output of a `credential_process`, see
+/// https://docs.aws.amazon.com/sdkref/latest/guide/feature-process-credentials.html
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProcessCredentials<'a> {
+    version: u8,
+    access_key_id: &'a str,
+    secret_access_key: &'a str,
+    expiration: String,
+}
+
+/// The command is split like a shell would, so the satori path and the datastore name are quoted
+/// when needed.
+fn get_credential_process_command(satori_path: &str, datastore_name: &str) -> String {
+    format!(
+        "{} aws credential-process {}",
+        quote(satori_path),
+        quote(datastore_name)
+    )
+}
+
+fn quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .any(|c| c.is_whitespace() || "\"'\\#;".contains(c))
+    {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
 fn get_ini_content_or_new(path: &Path) -> Ini {
-    match Ini::load_from_file(path) {
+    // The values are read as they are, like the AWS CLI and SDKs do.
+    let parse_option = ParseOption {
+        enabled_quote: false,
+        enabled_escape: false,
+    };
+    match Ini::load_from_file_opt(path, parse_option) {
         Ok(ini_content) => ini_content,
         Err(err) => {
             log::debug!("file not found: {}, generating new file", err);
@@ -105,7 +234,32 @@ fn get_hash_for_datastore(datastore_info: &DatastoreInfo, num_digits: u32) -> u6
     hash_value % divisor
 }
 
-fn remove_stale_satori_profiles(ini: &mut Ini, expected_profiles: &HashSet<String>) {
+fn get_sections(ini: &Ini) -> HashSet<String> {
+    ini.sections().flatten().map(str::to_string).collect()
+}
+
+/// The satori sections which would be added, updated and removed from the file.
+fn get_change(
+    path: PathBuf,
+    existing_sections: &HashSet<String>,
+    expected_sections: impl Iterator<Item = String>,
+    mut removed: Vec<String>,
+) -> FileChange {
+    let (mut updated, mut added): (Vec<String>, Vec<String>) =
+        expected_sections.partition(|section| existing_sections.contains(section));
+    added.sort();
+    updated.sort();
+    removed.sort();
+    FileChange {
+        path,
+        added,
+        updated,
+        removed,
+        ..Default::default()
+    }
+}
+
+fn remove_stale_satori_profiles(ini: &mut Ini, expected_profiles: &HashSet<String>) -> Vec<String> {
     let sections_to_remove: Vec<String> = ini
         .sections()
         .filter_map(|section_name| {
@@ -123,9 +277,13 @@ fn remove_stale_satori_profiles(ini: &mut Ini, expected_profiles: &HashSet<Strin
         log::info!("Removing stale profile: {}", section);
         ini.delete(Some(section));
     }
+    sections_to_remove
 }
 
-fn remove_stale_satori_profiles_from_config(ini: &mut Ini, expected_profiles: &HashSet<String>) {
+fn remove_stale_satori_profiles_from_config(
+    ini: &mut Ini,
+    expected_profiles: &HashSet<String>,
+) -> Vec<String> {
     let prefix = format!("profile {}", PROFILE_NAME_PREFIX);
     let sections_to_remove: Vec<String> = ini
         .sections()
@@ -152,6 +310,7 @@ fn remove_stale_satori_profiles_from_config(ini: &mut Ini, expected_profiles: &H
         log::info!("Removing stale config section: {}", section);
         ini.delete(Some(section));
     }
+    sections_to_remove
 }
 
 #[cfg(test)]
@@ -167,8 +326,10 @@ mod tests {
         let mut datastores_info = DatastoresInfo {
             datastores: HashMap::new(),
             account_id,
+            unusable_datastores: HashMap::new(),
         };
         let s3_datastore = DatastoreInfo {
+            id: "s3Id".to_string(),
             r#type: DatastoreType::S3,
             databases: vec!["db1".to_string(), "db2".to_string()],
             port: Some(1234),
@@ -176,6 +337,7 @@ mod tests {
             deployment_type: None,
         };
         let athena_datastore = DatastoreInfo {
+            id: "athenaId".to_string(),
             r#type: DatastoreType::Athena,
             databases: vec![],
             port: None,
@@ -183,6 +345,7 @@ mod tests {
             deployment_type: None,
         };
         let non_aws_datastore = DatastoreInfo {
+            id: "postgresId".to_string(),
             r#type: DatastoreType::Postgresql,
             databases: vec!["db1".to_string(), "db2".to_string()],
             port: Some(1234),
@@ -211,4 +374,16 @@ mod tests {
 
         assert_eq!(aws_datastores.len(), 2);
     }
+
+    #[test]
+    fn test_credential_process_command_quoting() {
+        assert_eq!(
+            get_credential_process_command("/usr/local/bin/satori", "athena1"),
+            "/usr/local/bin/satori aws credential-process athena1"
+        );
+        assert_eq!(
+            get_credential_process_command("/opt/my tools/satori", r#"my "s3" bucket#a1"#),
+            r#""/opt/my tools/satori" aws credential-process "my \"s3\" bucket#a1""#
+        );
+    }
 }
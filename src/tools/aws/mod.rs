@@ -1,5 +1,5 @@
 pub mod data;
 pub mod flow;
 
-pub use data::Aws;
-pub use flow::run;
+pub use data::{Aws, AwsCredentialProcess};
+pub use flow::{credential_process, run};
@@ -1,8 +1,15 @@
-use super::aws::Aws;
+use super::aws::{Aws, AwsCredentialProcess};
+use super::dbt::DbtInit;
 use super::pgpass::PgPass;
+use super::pgservice::PgService;
+use super::validate::Validate;
 
 #[derive(Debug)]
 pub enum Tools {
     PgPass(PgPass),
+    PgService(PgService),
     Aws(Aws),
+    AwsCredentialProcess(AwsCredentialProcess),
+    DbtInit(DbtInit),
+    Validate(Validate),
 }
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use derive_builder::Builder;
+
+use crate::{helpers::dry_run::DryRun, login::Login};
+
+/// Adds a target of a Satori datastore to a dbt profile.
+#[derive(Debug, Builder)]
+#[builder(pattern = "owned")]
+pub struct DbtInit {
+    pub login: Login,
+    pub datastore_name: String,
+    pub database: String,
+    pub schema: String,
+    pub target: String,
+    /// The profile of the dbt project when unset.
+    #[builder(default)]
+    pub profile_name: Option<String>,
+    #[builder(default)]
+    pub project_dir: Option<PathBuf>,
+    pub profiles_path: PathBuf,
+    /// Replaces the target when the profile already has it.
+    #[builder(default)]
+    pub force: bool,
+    /// The `s3_staging_dir` and `region_name` of Athena targets.
+    #[builder(default)]
+    pub s3_staging_dir: Option<String>,
+    #[builder(default)]
+    pub region: Option<String>,
+    /// Records the change to the profiles file instead of writing it.
+    #[builder(default)]
+    pub dry_run: Option<DryRun>,
+}
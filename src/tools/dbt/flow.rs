@@ -0,0 +1,272 @@
+use std::{fs, io, path::Path};
+
+use serde_yaml::{Mapping, Value};
+use similar::TextDiff;
+
+use crate::{
+    helpers::{datastores::DatastoreInfo, dry_run::FileChange},
+    login,
+    run::dbt::{self, Adapter, PASSWORD_ENV, USERNAME_ENV},
+    tools::errors,
+};
+
+use super::DbtInit;
+
+pub async fn run<R>(params: DbtInit, user_input_stream: R) -> Result<(), errors::ToolsError>
+where
+    R: std::io::BufRead,
+{
+    let profile_name = match &params.profile_name {
+        Some(profile_name) => profile_name.clone(),
+        None => dbt::get_project_profile(params.project_dir.as_deref())
+            .map_err(errors::ToolsError::DbtProjectError)?,
+    };
+    let content = read_profiles_file(&params.profiles_path)?;
+    let mut profiles = if content.trim().is_empty() {
+        Mapping::new()
+    } else {
+        serde_yaml::from_str::<Mapping>(&content).map_err(|err| {
+            errors::ToolsError::DbtProfilesParseError(params.profiles_path.clone(), err)
+        })?
+    };
+    let existing_profile = profiles
+        .get_mut(profile_name.as_str())
+        .and_then(Value::as_mapping_mut);
+    let has_target = existing_profile
+        .as_ref()
+        .and_then(|profile| profile.get("outputs"))
+        .and_then(|outputs| outputs.get(params.target.as_str()))
+        .is_some();
+    if has_target && !params.force {
+        return Err(errors::ToolsError::DbtTargetExists {
+            profile: profile_name,
+            target: params.target,
+        });
+    }
+
+    let (_, datastores_info) = login::run_with_file(&params.login, user_input_stream).await?;
+    let datastore_info = datastores_info.resolve(&params.datastore_name, None)?;
+    let target_values = get_target_values(&params, datastore_info)?;
+
+    // The rest of the file is kept as is, including its comments, anchors and quoting.
+    let new_content = match existing_profile {
+        Some(profile) => {
+            let outputs = profile
+                .entry("outputs".into())
+                .or_insert_with(|| Value::Mapping(Mapping::new()));
+            if !outputs.is_mapping() {
+                *outputs = Value::Mapping(Mapping::new());
+            }
+            outputs
+                .as_mapping_mut()
+                .unwrap()
+                .insert(params.target.as_str().into(), target_values.clone());
+            let target_text = to_yaml(
+                &params.profiles_path,
+                &Mapping::from_iter([(params.target.as_str().into(), target_values)]),
+            )?;
+            // The edit is only kept when the file reads back as the profiles with the target.
+            insert_target(&content, &profile_name, &params.target, &target_text)
+                .filter(|new_content| {
+                    serde_yaml::from_str::<Mapping>(new_content).is_ok_and(|new| new == profiles)
+                })
+                .ok_or_else(|| errors::ToolsError::DbtProfileNotEditable {
+                    path: params.profiles_path.clone(),
+                    profile: profile_name.clone(),
+                    target_text: indent(&target_text, "    "),
+                })?
+        }
+        None => {
+            let new_profile = Mapping::from_iter([(
+                Value::from(profile_name.as_str()),
+                Value::Mapping(Mapping::from_iter([
+                    ("target".into(), params.target.as_str().into()),
+                    (
+                        "outputs".into(),
+                        Value::Mapping(Mapping::from_iter([(
+                            params.target.as_str().into(),
+                            target_values,
+                        )])),
+                    ),
+                ])),
+            )]);
+            format!(
+                "{content}{}{}",
+                line_separator(&content),
+                to_yaml(&params.profiles_path, &new_profile)?
+            )
+        }
+    };
+
+    if let Some(dry_run) = &params.dry_run {
+        let path = params.profiles_path.display().to_string();
+        let diff = TextDiff::from_lines(&content, &new_content)
+            .unified_diff()
+            .header(&path, &path)
+            .to_string();
+        dry_run.record_file(FileChange {
+            path: params.profiles_path,
+            diff: Some(diff),
+            ..Default::default()
+        });
+        return Ok(());
+    }
+
+    if let Some(parent) = params.profiles_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| {
+            errors::ToolsError::FailedToWriteToFile(err, params.profiles_path.clone())
+        })?;
+    }
+    fs::write(&params.profiles_path, new_content).map_err(|err| {
+        errors::ToolsError::FailedToWriteToFile(err, params.profiles_path.clone())
+    })?;
+    log::info!(
+        "Added the target {} of {} to the dbt profile {profile_name} in {}",
+        params.target,
+        params.datastore_name,
+        params.profiles_path.display()
+    );
+    Ok(())
+}
+
+fn read_profiles_file(path: &Path) -> Result<String, errors::ToolsError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            log::debug!("dbt profiles file not found: {}, generating new file", err);
+            Ok(String::new())
+        }
+        Err(err) => Err(errors::ToolsError::DbtProfilesReadError(
+            path.to_path_buf(),
+            err,
+        )),
+    }
+}
+
+/// Adds the target at the end of the `outputs` of the profile, or replaces the lines of the
+/// existing target, none when the profile isn't written as a block mapping with `outputs`.
+fn insert_target(
+    content: &str,
+    profile_name: &str,
+    target: &str,
+    target_text: &str,
+) -> Option<String> {
+    let lines = content.split_inclusive('\n').collect::<Vec<&str>>();
+    let is_content = |index: &usize| {
+        let line = lines[*index].trim();
+        !line.is_empty() && !line.starts_with('#')
+    };
+    let indent_of = |index: usize| lines[index].len() - lines[index].trim_start().len();
+    // The end of the block of the line, the lines after it which are indented deeper.
+    let block_end = |start: usize, end: usize| {
+        (start + 1..end)
+            .filter(is_content)
+            .take_while(|index| indent_of(*index) > indent_of(start))
+            .last()
+            .map_or(start + 1, |index| index + 1)
+    };
+    let find_key = |key: &str, range: std::ops::Range<usize>, indent: usize| {
+        range
+            .filter(is_content)
+            .filter(|index| indent_of(*index) == indent)
+            .find(|index| parse_key(lines[*index]) == Some((key, true)))
+    };
+
+    let profile_start = find_key(profile_name, 0..lines.len(), 0)?;
+    let profile_end = block_end(profile_start, lines.len());
+    let profile_indent = indent_of((profile_start + 1..profile_end).find(is_content)?);
+    let outputs_start = find_key("outputs", profile_start + 1..profile_end, profile_indent)?;
+    let outputs_end = block_end(outputs_start, profile_end);
+    let target_indent = (outputs_start + 1..outputs_end)
+        .find(is_content)
+        .map_or(indent_of(outputs_start) + 2, indent_of);
+    let replaced = match find_key(target, outputs_start + 1..outputs_end, target_indent) {
+        Some(target_start) => target_start..block_end(target_start, outputs_end),
+        None => outputs_end..outputs_end,
+    };
+
+    let before = lines[..replaced.start].concat();
+    Some(format!(
+        "{before}{}{}{}",
+        line_separator(&before),
+        indent(target_text, &" ".repeat(target_indent)),
+        lines[replaced.end..].concat()
+    ))
+}
+
+/// The key of a `key:` line, and whether its value is a nested block, which can have an anchor.
+fn parse_key(line: &str) -> Option<(&str, bool)> {
+    let (key, value) = line.trim().split_once(':')?;
+    let key = key.trim().trim_matches(|c| c == '\'' || c == '"');
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    let is_block = value.is_empty() || (value.starts_with('&') && !value.contains(' '));
+    Some((key, is_block))
+}
+
+fn indent(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| format!("{indent}{line}\n"))
+        .collect()
+}
+
+/// The newline to add before appending to the content.
+fn line_separator(content: &str) -> &'static str {
+    if content.is_empty() || content.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    }
+}
+
+fn to_yaml(path: &Path, value: &Mapping) -> Result<String, errors::ToolsError> {
+    serde_yaml::to_string(value)
+        .map_err(|err| errors::ToolsError::DbtProfilesParseError(path.to_path_buf(), err))
+}
+
+/// The target of the datastore, with the credentials read from environment variables.
+fn get_target_values(
+    params: &DbtInit,
+    datastore_info: &DatastoreInfo,
+) -> Result<Value, errors::ToolsError> {
+    let adapter = Adapter::for_datastore(&datastore_info.r#type).ok_or_else(|| {
+        errors::ToolsError::DbtUnsupportedDatastore(
+            params.datastore_name.clone(),
+            datastore_info.r#type.clone(),
+        )
+    })?;
+    // The variables `satori run dbt` sets.
+    let username = format!("{{{{ env_var('{USERNAME_ENV}') }}}}");
+    let password = format!("{{{{ env_var('{PASSWORD_ENV}') }}}}");
+    let mut target = Mapping::new();
+    target.insert("type".into(), adapter.name().into());
+    if adapter.uses_host() {
+        target.insert("host".into(), datastore_info.satori_host.as_str().into());
+        if let Some(port) = datastore_info
+            .port
+            .or_else(|| datastore_info.r#type.default_port())
+        {
+            target.insert("port".into(), port.into());
+        }
+        target.insert("user".into(), username.into());
+        target.insert("password".into(), password.into());
+        target.insert("dbname".into(), params.database.as_str().into());
+    } else {
+        for (field, argument, value) in [
+            ("s3_staging_dir", "--s3-staging-dir", &params.s3_staging_dir),
+            ("region_name", "--region", &params.region),
+        ] {
+            let value = value
+                .as_deref()
+                .ok_or_else(|| errors::ToolsError::DbtMissingArgument {
+                    adapter: adapter.name(),
+                    argument,
+                })?;
+            target.insert(field.into(), value.into());
+        }
+        target.insert("aws_access_key_id".into(), username.into());
+        target.insert("aws_secret_access_key".into(), password.into());
+        target.insert("database".into(), params.database.as_str().into());
+    }
+    target.insert("schema".into(), params.schema.as_str().into());
+    Ok(Value::Mapping(target))
+}
@@ -1,4 +1,12 @@
-use crate::helpers::datastores::errors::{DatastoresError, GetHostError};
+use std::path::PathBuf;
+
+use crate::{
+    helpers::{
+        datastores::errors::{DatastoresError, GetHostError, ResolveDatastoreError},
+        satori_console::DatastoreType,
+    },
+    run::errors::RunError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ToolsError {
@@ -16,10 +24,60 @@ pub enum ToolsError {
     DatastoresError(#[from] DatastoresError),
     #[error("Failed writing to pgpass file")]
     FailedWritingToPgpassFile(std::io::Error),
+    #[error("Invalid datastore pattern `{0}`: {1}")]
+    InvalidDatastorePattern(String, glob::PatternError),
+    #[error("Invalid satori block in the pgpass file at line {line}: {reason}")]
+    InvalidPgpassBlock { line: usize, reason: &'static str },
+    #[error("Datastore {0} is a {1:?} datastore, AWS credentials can be generated for S3 and Athena datastores")]
+    AwsUnsupportedDatastore(String, DatastoreType),
+    #[error("Failed to get the path of satori for the AWS credential process: {0}")]
+    SatoriPathError(std::io::Error),
+    #[error("Failed to read the service file {0}: {1}")]
+    PgServiceReadError(PathBuf, std::io::Error),
+    #[error("Invalid satori block in the service file at line {line}: {reason}")]
+    InvalidPgServiceBlock { line: usize, reason: &'static str },
     #[error("Read line error: {0}")]
     ReadLineError(std::io::Error),
     #[error("Failed to write to file {1}: {0}")]
     FailedToWriteToFile(std::io::Error, std::path::PathBuf),
     #[error("{0}")]
     GetHostError(#[from] GetHostError),
+    #[error("Failed to read tools file {1}: {0}")]
+    FailedToReadToolsFile(std::io::Error, std::path::PathBuf),
+    #[error("Found {0} problems in the tools definitions")]
+    InvalidTools(usize),
+    #[error("Failed to write the validation result: {0}")]
+    WriteError(std::io::Error),
+    #[error("{0}")]
+    DatastoreResolveError(#[from] ResolveDatastoreError),
+    #[error("{0}")]
+    DbtProjectError(RunError),
+    #[error("Failed to read dbt profiles file {0}: {1}")]
+    DbtProfilesReadError(PathBuf, std::io::Error),
+    #[error("Failed to parse dbt profiles file {0}: {1}")]
+    DbtProfilesParseError(PathBuf, serde_yaml::Error),
+    #[error("Could not add the target to the DBT profile {profile} of {path} without changing the rest of the file, add it under the outputs of the profile:\n{target_text}")]
+    DbtProfileNotEditable {
+        path: PathBuf,
+        profile: String,
+        target_text: String,
+    },
+    #[error("DBT profile {profile} already has the target {target}, use --force to replace it")]
+    DbtTargetExists { profile: String, target: String },
+    #[error("Datastore {0} is a {1:?} datastore, dbt profiles can be generated for PostgreSQL, Redshift, Greenplum, CockroachDB and Athena datastores")]
+    DbtUnsupportedDatastore(String, DatastoreType),
+    #[error("DBT {adapter} targets require the {argument} argument")]
+    DbtMissingArgument {
+        adapter: &'static str,
+        argument: &'static str,
+    },
+}
+
+/// Why a line of the pgpass file isn't an entry.
+#[derive(thiserror::Error, Debug)]
+pub enum PgPassLineError {
+    #[error("expected 5 fields separated by `:`, found {0}")]
+    MissingFields(usize),
+    #[error("invalid port `{0}`")]
+    InvalidPort(String),
 }
@@ -1,4 +1,6 @@
-use super::{aws, errors, pgpass, Tools};
+use std::io;
+
+use super::{aws, dbt, errors, pgpass, pgservice, validate, Tools};
 
 pub async fn run<R>(params: Tools, user_input_stream: R) -> Result<(), errors::ToolsError>
 where
@@ -6,6 +8,12 @@ where
 {
     match params {
         Tools::PgPass(pg_pass) => pgpass::run(pg_pass, user_input_stream).await,
+        Tools::PgService(pg_service) => pgservice::run(pg_service, user_input_stream).await,
         Tools::Aws(aws) => aws::run(aws, user_input_stream).await,
+        Tools::AwsCredentialProcess(credential_process) => {
+            aws::credential_process(&credential_process, user_input_stream, &mut io::stdout()).await
+        }
+        Tools::DbtInit(dbt_init) => dbt::run(dbt_init, user_input_stream).await,
+        Tools::Validate(validate) => validate::run(&validate, &mut io::stdout()),
     }
 }
@@ -1,8 +1,11 @@
 pub mod aws;
 pub mod data;
+pub mod dbt;
 pub mod errors;
 pub mod flow;
 pub mod pgpass;
+pub mod pgservice;
+pub mod validate;
 
 pub use data::Tools;
 pub use flow::run;
@@ -2,11 +2,26 @@ use std::path::PathBuf;
 
 use derive_builder::Builder;
 
-use crate::login::Login;
+use crate::{
+    helpers::{dry_run::DryRun, satori_console::DatastoreType},
+    login::Login,
+};
 
 #[derive(Debug, Builder)]
 #[builder(pattern = "owned")]
 pub struct PgPass {
     pub login: Login,
     pub path: PathBuf,
+    /// Datastore names or glob patterns, all the datastores when empty.
+    #[builder(default)]
+    pub datastores: Vec<String>,
+    /// Datastore types, all the postgres dialect types when empty.
+    #[builder(default)]
+    pub types: Vec<DatastoreType>,
+    /// Writes a single `host:port:*` entry per datastore instead of an entry per database.
+    #[builder(default)]
+    pub wildcard_databases: bool,
+    /// Records the changes to the files instead of writing them.
+    #[builder(default)]
+    pub dry_run: Option<DryRun>,
 }
@@ -0,0 +1,450 @@
+//! The libpq password file: `hostname:port:database:username:password` lines, where `\` escapes
+//! `:` and `\`, a `*` field matches anything, and lines starting with `#` are comments.
+
+use core::fmt;
+use std::str::FromStr;
+
+use crate::{
+    helpers::secret::Secret,
+    tools::errors::{PgPassLineError, ToolsError},
+};
+
+const WILDCARD: &str = "*";
+const BLOCK_BEGIN: &str = "# BEGIN satori entries, regenerated by `satori pgpass`";
+const BLOCK_END: &str = "# END satori entries";
+
+/// The lines of a pgpass file. The satori entries are kept in a marked block, which is
+/// regenerated on every run, every other line is written back as it was read.
+#[derive(Debug, Default)]
+pub struct PgPassFile {
+    lines: Vec<PgPassLine>,
+}
+
+#[derive(Debug)]
+enum PgPassLine {
+    /// An entry and its line as it was read.
+    Entry(PgPassEntry, String),
+    /// A comment, a blank line or an invalid line.
+    Other(String),
+}
+
+impl PgPassFile {
+    /// Parses the file content, the invalid lines are kept as is and returned with their line
+    /// number.
+    pub fn parse(content: &str) -> (Self, Vec<(usize, PgPassLineError)>) {
+        let mut invalid_lines = Vec::new();
+        let lines = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                if line.is_empty() || line.starts_with('#') {
+                    return PgPassLine::Other(line.to_string());
+                }
+                match line.parse::<PgPassEntry>() {
+                    Ok(entry) => PgPassLine::Entry(entry, line.to_string()),
+                    Err(err) => {
+                        invalid_lines.push((index + 1, err));
+                        PgPassLine::Other(line.to_string())
+                    }
+                }
+            })
+            .collect();
+        (Self { lines }, invalid_lines)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &PgPassEntry> {
+        self.lines.iter().filter_map(|line| match line {
+            PgPassLine::Entry(entry, _) => Some(entry),
+            PgPassLine::Other(_) => None,
+        })
+    }
+
+    /// Regenerates the satori block with the entries, the lines outside of it aren't changed.
+    /// The block is added at the top of the file, as libpq uses the first matching entry.
+    /// When there is no block yet, the entries written by earlier versions, the ones `is_satori`
+    /// matches, are moved to the block.
+    pub fn replace_satori_block(
+        &mut self,
+        entries: Vec<PgPassEntry>,
+        is_satori: impl Fn(&PgPassEntry) -> bool,
+    ) -> Result<PgPassChange, ToolsError> {
+        let old_entries = self.satori_entries(&is_satori)?;
+        let is_existing = |entry: &PgPassEntry, entries: &[PgPassEntry]| {
+            entries.iter().any(|other| other.key() == entry.key())
+        };
+        let mut change = PgPassChange::default();
+        for entry in &entries {
+            if is_existing(entry, &old_entries) {
+                change.updated.push(entry.clone());
+            } else {
+                change.added.push(entry.clone());
+            }
+        }
+        change.removed = old_entries
+            .iter()
+            .filter(|entry| !is_existing(entry, &entries))
+            .cloned()
+            .collect();
+
+        let (start, end) = match self.find_satori_block()? {
+            Some(block) => block,
+            None => {
+                self.lines.retain(
+                    |line| !matches!(line, PgPassLine::Entry(entry, _) if is_satori(entry)),
+                );
+                (0, 0)
+            }
+        };
+        let block = if entries.is_empty() {
+            vec![]
+        } else {
+            std::iter::once(PgPassLine::Other(BLOCK_BEGIN.to_string()))
+                .chain(
+                    entries
+                        .iter()
+                        .map(|entry| PgPassLine::Entry(entry.clone(), entry.to_string())),
+                )
+                .chain(std::iter::once(PgPassLine::Other(BLOCK_END.to_string())))
+                .collect()
+        };
+        let block_len = block.len();
+        self.lines.splice(start..end, block);
+
+        change.shadowed = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index < start || *index >= start + block_len)
+            .filter_map(|(index, line)| match line {
+                PgPassLine::Entry(entry, _) if is_existing(entry, &entries) => Some(index + 1),
+                _ => None,
+            })
+            .collect();
+        Ok(change)
+    }
+
+    /// The entries of the satori block, or when there is no block yet, the entries `is_satori`
+    /// matches.
+    pub fn satori_entries(
+        &self,
+        is_satori: impl Fn(&PgPassEntry) -> bool,
+    ) -> Result<Vec<PgPassEntry>, ToolsError> {
+        let entries = match self.find_satori_block()? {
+            Some((start, end)) => self.lines[start..end]
+                .iter()
+                .filter_map(|line| match line {
+                    PgPassLine::Entry(entry, _) => Some(entry.clone()),
+                    PgPassLine::Other(_) => None,
+                })
+                .collect(),
+            None => self
+                .entries()
+                .filter(|entry| is_satori(entry))
+                .cloned()
+                .collect(),
+        };
+        Ok(entries)
+    }
+
+    /// The range of lines of the satori block, including its markers.
+    fn find_satori_block(&self) -> Result<Option<(usize, usize)>, ToolsError> {
+        let lines = self.lines.iter().map(|line| match line {
+            PgPassLine::Other(text) => Some(text.as_str()),
+            PgPassLine::Entry(..) => None,
+        });
+        find_block(lines, BLOCK_BEGIN, BLOCK_END)
+            .map_err(|(line, reason)| ToolsError::InvalidPgpassBlock { line, reason })
+    }
+}
+
+/// The range of lines, including the markers, of the block between the `begin` and `end` marker
+/// lines. `None` lines can't be markers. The error has the line number and the reason.
+pub(crate) fn find_block<'a>(
+    lines: impl Iterator<Item = Option<&'a str>>,
+    begin_marker: &str,
+    end_marker: &str,
+) -> Result<Option<(usize, usize)>, (usize, &'static str)> {
+    let mut begin = None;
+    let mut block = None;
+    for (index, line) in lines.enumerate() {
+        let Some(text) = line else {
+            continue;
+        };
+        let text = text.trim();
+        if text == begin_marker {
+            if begin.is_some() || block.is_some() {
+                return Err((index + 1, "a second satori block"));
+            }
+            begin = Some(index);
+        } else if text == end_marker {
+            match (begin, block) {
+                (Some(start), None) => {
+                    block = Some((start, index + 1));
+                    begin = None;
+                }
+                _ => return Err((index + 1, "the end of a block which wasn't started")),
+            }
+        }
+    }
+    match begin {
+        Some(start) => Err((start + 1, "the satori block isn't ended")),
+        None => Ok(block),
+    }
+}
+
+/// The satori entries added, updated and removed from the block, and the line numbers of entries
+/// outside of it which match a satori entry.
+#[derive(Debug, Default)]
+pub struct PgPassChange {
+    pub added: Vec<PgPassEntry>,
+    pub updated: Vec<PgPassEntry>,
+    pub removed: Vec<PgPassEntry>,
+    pub shadowed: Vec<usize>,
+}
+
+impl fmt::Display for PgPassFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                PgPassLine::Entry(_, line) | PgPassLine::Other(line) => writeln!(f, "{line}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A pgpass entry, `*` fields match any value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PgPassEntry {
+    pub host: String,
+    pub port: String,
+    pub database: String,
+    pub username: String,
+    pub password: Secret,
+}
+
+impl PgPassEntry {
+    pub fn new(
+        host: String,
+        port: u16,
+        database: String,
+        username: String,
+        password: Secret,
+    ) -> Self {
+        Self {
+            host,
+            port: port.to_string(),
+            database,
+            username,
+            password,
+        }
+    }
+
+    /// The entry as it is written to the file, without the password.
+    pub fn without_password(&self) -> String {
+        [&self.host, &self.port, &self.database, &self.username]
+            .map(|field| escape(field))
+            .join(":")
+    }
+
+    fn key(&self) -> (&str, &str, &str) {
+        (&self.host, &self.port, &self.database)
+    }
+}
+
+impl FromStr for PgPassEntry {
+    type Err = PgPassLineError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields = split_fields(line);
+        // Like libpq, fields after the password are ignored.
+        let [host, port, database, username, password, ..] = fields.as_slice() else {
+            return Err(PgPassLineError::MissingFields(fields.len()));
+        };
+        if port != WILDCARD && port.parse::<u16>().is_err() {
+            return Err(PgPassLineError::InvalidPort(port.clone()));
+        }
+        Ok(Self {
+            host: host.clone(),
+            port: port.clone(),
+            database: database.clone(),
+            username: username.clone(),
+            password: Secret::new(password.as_str()),
+        })
+    }
+}
+
+impl fmt::Display for PgPassEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}",
+            self.without_password(),
+            escape(self.password.expose())
+        )
+    }
+}
+
+/// Splits the line on the unescaped `:`, removing the escapes.
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars.next().unwrap_or('\\');
+                fields.last_mut().unwrap().push(escaped);
+            }
+            ':' => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+fn escape(field: &str) -> String {
+    field.replace('\\', r"\\").replace(':', r"\:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_other_lines() {
+        let content = "# my databases\n\
+                       \n\
+                       *:*:*:me:p\\:ss\\\\word\n\
+                       broken line\n\
+                       host:port:db:user:password\n\
+                       host:5432:db:user:password\n";
+        let (mut pgpass, invalid_lines) = PgPassFile::parse(content);
+
+        assert_eq!(
+            invalid_lines
+                .iter()
+                .map(|(line_number, err)| format!("{line_number}: {err}"))
+                .collect::<Vec<String>>(),
+            vec![
+                "4: expected 5 fields separated by `:`, found 1",
+                "5: invalid port `port`",
+            ]
+        );
+        let wildcard = pgpass.entries().next().unwrap();
+        assert_eq!(wildcard.port, "*");
+        assert_eq!(wildcard.password.expose(), r"p:ss\word");
+
+        let change = pgpass
+            .replace_satori_block(
+                vec![PgPassEntry::new(
+                    "host".to_string(),
+                    5432,
+                    "db".to_string(),
+                    "satori".to_string(),
+                    Secret::from("new:password"),
+                )],
+                |_| false,
+            )
+            .unwrap();
+        assert_eq!(change.added.len(), 1);
+        assert_eq!(change.shadowed, vec![9]);
+        assert_eq!(
+            pgpass.to_string(),
+            format!(
+                "{BLOCK_BEGIN}\n\
+                 host:5432:db:satori:new\\:password\n\
+                 {BLOCK_END}\n\
+                 # my databases\n\
+                 \n\
+                 *:*:*:me:p\\:ss\\\\word\n\
+                 broken line\n\
+                 host:port:db:user:password\n\
+                 host:5432:db:user:password\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_satori_block_is_regenerated() {
+        let content = format!(
+            "local:5432:db:me:password\n\
+             {BLOCK_BEGIN}\n\
+             stale:5432:db:satori:old\n\
+             host:5432:db:satori:old\n\
+             {BLOCK_END}\n\
+             # after the block\n"
+        );
+        let (mut pgpass, _) = PgPassFile::parse(&content);
+        let change = pgpass
+            .replace_satori_block(
+                vec![PgPassEntry::new(
+                    "host".to_string(),
+                    5432,
+                    "db".to_string(),
+                    "satori".to_string(),
+                    Secret::from("new"),
+                )],
+                |entry| entry.host == "local",
+            )
+            .unwrap();
+
+        assert!(change.added.is_empty());
+        assert_eq!(change.updated.len(), 1);
+        assert_eq!(change.removed[0].host, "stale");
+        assert_eq!(
+            pgpass.to_string(),
+            format!(
+                "local:5432:db:me:password\n\
+                 {BLOCK_BEGIN}\n\
+                 host:5432:db:satori:new\n\
+                 {BLOCK_END}\n\
+                 # after the block\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_unmarked_satori_entries_are_moved_to_the_block() {
+        let content = "# my databases\n\
+                       host:5432:db:satori:old\n\
+                       local:5432:db:me:password\n\
+                       host:5432:gone:satori:old\n";
+        let (mut pgpass, _) = PgPassFile::parse(content);
+        let change = pgpass
+            .replace_satori_block(
+                vec![PgPassEntry::new(
+                    "host".to_string(),
+                    5432,
+                    "db".to_string(),
+                    "satori".to_string(),
+                    Secret::from("new"),
+                )],
+                |entry| entry.host == "host",
+            )
+            .unwrap();
+
+        assert_eq!(change.updated.len(), 1);
+        assert_eq!(change.removed[0].database, "gone");
+        assert!(change.shadowed.is_empty());
+        assert_eq!(
+            pgpass.to_string(),
+            format!(
+                "{BLOCK_BEGIN}\n\
+                 host:5432:db:satori:new\n\
+                 {BLOCK_END}\n\
+                 # my databases\n\
+                 local:5432:db:me:password\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_satori_block_not_ended() {
+        let (mut pgpass, _) = PgPassFile::parse(&format!("{BLOCK_BEGIN}\nhost:5432:db:u:p\n"));
+        let err = pgpass.replace_satori_block(vec![], |_| true).unwrap_err();
+        assert!(
+            matches!(err, ToolsError::InvalidPgpassBlock { line: 1, .. }),
+            "{err}"
+        );
+    }
+}
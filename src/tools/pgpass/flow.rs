@@ -1,22 +1,28 @@
-use core::fmt;
-use std::hash::Hash;
 #[cfg(target_family = "unix")]
 use std::os::unix::fs::OpenOptionsExt;
 use std::{
-    collections::HashSet,
-    fs::{File, OpenOptions},
-    hash::Hasher,
-    io::{BufRead, BufReader, Seek, SeekFrom, Write},
-    path::PathBuf,
+    collections::{BTreeSet, HashSet},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
 };
 
+use glob::Pattern;
+
 use crate::{
-    helpers::datastores::DatastoresInfo,
+    helpers::{
+        datastores::{DatastoreInfo, DatastoresInfo},
+        dry_run::FileChange,
+        satori_console::DatastoreType,
+    },
     login::{self, data::Credentials},
     tools::errors,
 };
 
-use super::PgPass;
+use super::{
+    file::{PgPassEntry, PgPassFile},
+    PgPass,
+};
 
 pub async fn run<R>(params: PgPass, user_input_stream: R) -> Result<(), errors::ToolsError>
 where
@@ -25,56 +31,90 @@ where
     let (credentials, datastores_info) =
         login::run_with_file(&params.login, user_input_stream).await?;
 
-    let satori_pgpass = pgpass_from_satori_db(&datastores_info, &credentials);
+    let mut satori_pgpass = pgpass_from_satori_db(&params, &datastores_info, &credentials)?;
     log::debug!("Satori pgpass: {satori_pgpass:?}");
 
     let pgpass_file = params.path;
-    if pgpass_file.exists() {
+    let content = read_pgpass_file(&pgpass_file)?;
+    let (mut pgpass, invalid_lines) = PgPassFile::parse(&content);
+    for (line_number, err) in invalid_lines {
+        log::warn!(
+            "{}:{line_number}: {err}, the line is kept as is",
+            pgpass_file.display()
+        );
+    }
+    let satori_hosts = get_satori_hosts(datastores_info.datastores.values())?;
+    let selected_hosts = get_satori_hosts(
+        get_postgres_datastores(&params.datastores, &params.types, &datastores_info)?
+            .into_iter()
+            .map(|(_, datastore_info)| datastore_info),
+    )?;
+    let host_of = |entry: &PgPassEntry| (entry.host.clone(), entry.port.clone());
+    let is_satori_host = |entry: &PgPassEntry| satori_hosts.contains(&host_of(entry));
+    // Without a block, only the entries of the satori user are taken as written by an earlier
+    // version, the entries of other users of the same hosts are the user's own.
+    let is_satori =
+        |entry: &PgPassEntry| is_satori_host(entry) && entry.username == credentials.username;
+    // The filters select the datastores which are refreshed, the entries of the other datastores
+    // are kept, only the entries of datastores which are gone are removed.
+    let kept = pgpass
+        .satori_entries(is_satori)?
+        .into_iter()
+        .filter(|entry| is_satori_host(entry) && !selected_hosts.contains(&host_of(entry)))
+        .collect::<Vec<PgPassEntry>>();
+    satori_pgpass.extend(kept.iter().cloned());
+    let mut change = pgpass.replace_satori_block(satori_pgpass.into_iter().collect(), is_satori)?;
+    change.updated.retain(|entry| !kept.contains(entry));
+    for line_number in &change.shadowed {
+        log::warn!(
+            "{}:{line_number}: the entry has the host, port and database of a satori entry, libpq uses the first of them",
+            pgpass_file.display()
+        );
+    }
+
+    if let Some(dry_run) = &params.dry_run {
+        let to_strings = |entries: &[PgPassEntry]| {
+            entries
+                .iter()
+                .map(PgPassEntry::without_password)
+                .collect::<Vec<String>>()
+        };
+        dry_run.record_file(FileChange {
+            added: to_strings(&change.added),
+            updated: to_strings(&change.updated),
+            removed: to_strings(&change.removed),
+            path: pgpass_file,
+            ..Default::default()
+        });
+        return Ok(());
+    }
+
+    let mut file = if pgpass_file.exists() {
         log::debug!("Pgpass file exists, updating it");
-        let mut file = get_pgpass_file(pgpass_file)?;
-        let existing_pgpass = pgpass_from_file(&file)?;
-
-        let non_satori_entries = existing_pgpass
-            .difference(&satori_pgpass)
-            .collect::<HashSet<&PgPassEntry>>();
-        let satori_entries = satori_pgpass
-            .difference(&existing_pgpass)
-            .collect::<HashSet<&PgPassEntry>>();
-        let satori_exiting_entries = satori_pgpass
-            .intersection(&existing_pgpass)
-            .collect::<HashSet<&PgPassEntry>>();
-
-        file.seek(SeekFrom::Start(0)).unwrap();
-        file.set_len(0).unwrap();
-        for entry in non_satori_entries {
-            writeln!(file, "{entry}").map_err(errors::ToolsError::FailedWritingToPgpassFile)?;
-        }
-        for entry in satori_entries {
-            writeln!(file, "{entry}").map_err(errors::ToolsError::FailedWritingToPgpassFile)?;
-        }
-        for entry in satori_exiting_entries {
-            writeln!(file, "{entry}").map_err(errors::ToolsError::FailedWritingToPgpassFile)?;
-        }
+        OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(&pgpass_file)
+            .map_err(errors::ToolsError::FailedToOpenPgpassFile)?
     } else {
-        let mut file = create_pgpass_file(pgpass_file)?;
-        log::debug!("Created pgpass file at {file:?}");
-        for entry in satori_pgpass {
-            writeln!(file, "{entry}").map_err(errors::ToolsError::FailedWritingToPgpassFile)?;
-        }
-    }
+        log::debug!("Creating pgpass file at {pgpass_file:?}");
+        create_pgpass_file(&pgpass_file)?
+    };
+    write!(file, "{pgpass}").map_err(errors::ToolsError::FailedWritingToPgpassFile)?;
 
     Ok(())
 }
 
-fn get_pgpass_file(pgpass_file: PathBuf) -> Result<File, errors::ToolsError> {
-    OpenOptions::new()
-        .read(true)
-        .write(true)
-        .open(pgpass_file)
-        .map_err(errors::ToolsError::FailedToOpenPgpassFile)
+/// The content of the pgpass file, empty when there is no file yet.
+fn read_pgpass_file(pgpass_file: &Path) -> Result<String, errors::ToolsError> {
+    match fs::read_to_string(pgpass_file) {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(errors::ToolsError::FailedToOpenPgpassFile(err)),
+    }
 }
 
-fn create_pgpass_file(pgpass_file: PathBuf) -> Result<File, errors::ToolsError> {
+fn create_pgpass_file(pgpass_file: &Path) -> Result<fs::File, errors::ToolsError> {
     let mut open_options = OpenOptions::new();
     open_options.write(true).create(true);
 
@@ -94,126 +134,99 @@ fn set_permissions(_open_options: &mut OpenOptions) {
     log::debug!("Need to implement the windows mode");
 }
 
-#[derive(Eq, Ord, PartialOrd)]
-pub struct PgPassEntry {
-    host: String,
-    port: u16,
-    database: String,
-    username: String,
-    password: String,
-}
-
-impl PartialEq for PgPassEntry {
-    fn eq(&self, other: &Self) -> bool {
-        self.host == other.host && self.port == other.port && self.database == other.database
-    }
-}
-
-impl Hash for PgPassEntry {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.host.hash(state);
-        self.port.hash(state);
-        self.database.hash(state);
-    }
-}
-impl PgPassEntry {
-    fn from_creds(credentials: Credentials, port: u16, host: String, database: String) -> Self {
-        PgPassEntry {
-            host,
-            port,
-            database,
-            username: credentials.username,
-            password: credentials.password,
-        }
-    }
-}
-
-impl From<String> for PgPassEntry {
-    fn from(entry: String) -> Self {
-        let mut entry = entry.split(':');
-        PgPassEntry {
-            host: entry.next().unwrap().to_string(),
-            port: entry.next().unwrap().parse::<u16>().unwrap(),
-            database: entry.next().unwrap().to_string(),
-            username: entry.next().unwrap().to_string(),
-            password: entry.next().unwrap().to_string(),
-        }
-    }
-}
-impl fmt::Display for PgPassEntry {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}:{}:{}:{}:{}",
-            self.host, self.port, self.database, self.username, self.password
-        )
-    }
-}
-
-impl fmt::Debug for PgPassEntry {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "PgPassEntry {{ host: {}, port: {}, database: {}, username: {}, password: ******* }}",
-            self.host, self.port, self.database, self.username
-        )
-    }
-}
-
 fn pgpass_from_satori_db(
+    params: &PgPass,
     datastores_info: &DatastoresInfo,
     credentials: &Credentials,
-) -> HashSet<PgPassEntry> {
-    let pg_datastores = datastores_info
-        .datastores
-        .values()
-        .filter(|info| {
-            let is_postgres_datastore = info.r#type.is_postgres_dialect();
-            log::debug!("Datastore is postgres: {is_postgres_datastore}, datastore: {info:?}");
-
-            let has_port = if info.port.is_some() {
-                true
-            } else {
-                log::debug!("Datastore info: {info:?} has no port, not adding to pgpass");
-                false
-            };
-            is_postgres_datastore && has_port
-})
-        .flat_map(|datastore_info| {
+) -> Result<BTreeSet<PgPassEntry>, errors::ToolsError> {
+    let mut pg_datastores = BTreeSet::new();
+    for (datastore_name, datastore_info) in
+        get_postgres_datastores(&params.datastores, &params.types, datastores_info)?
+    {
+        let Some(port) = datastore_info
+            .port
+            .or_else(|| datastore_info.r#type.default_port())
+        else {
+            log::debug!("Datastore info: {datastore_info:?} has no port, not adding to pgpass");
+            continue;
+        };
+        let host = datastore_info.get_datastore_name()?;
+        let databases = if params.wildcard_databases {
+            vec!["*".to_string()]
+        } else {
             if datastore_info.databases.is_empty() {
-                log::warn!("Datastore info: {datastore_info:?} has no databases, not adding to pgpass");
+                log::warn!("Datastore {datastore_name} has no databases, not adding to pgpass, use --wildcard-databases to add it");
             }
-            datastore_info
-                .databases
-                .iter()
-                .map(|database| {
-                    log::debug!("Adding Datastore info: {datastore_info:?} to pgpass with database: {database}");
-                    PgPassEntry::from_creds(
-                        credentials.clone(),
-                        datastore_info.port.expect("Unexpected missing port"),
-                        datastore_info
-                            .get_datastore_name()
-                            .expect("Failed to get satori host")
-                            .clone(),
-                        database.clone(),
-                    )
-                }
-            )
-                .collect::<HashSet<PgPassEntry>>()
-        })
-        .collect::<HashSet<PgPassEntry>>();
+            datastore_info.databases.clone()
+        };
+        for database in databases {
+            log::debug!("Adding datastore {datastore_name} to pgpass with database: {database}");
+            pg_datastores.insert(PgPassEntry::new(
+                host.clone(),
+                port,
+                database,
+                credentials.username.clone(),
+                credentials.password.clone(),
+            ));
+        }
+    }
     if pg_datastores.is_empty() {
         log::warn!("No postgres datastores are available, go to the Satori Data Portal and verify that you have access to the relevant dataset. Also ensure that you have satori authentication for the datastore")
     }
-    pg_datastores
+    Ok(pg_datastores)
 }
 
-fn pgpass_from_file(file: &File) -> Result<HashSet<PgPassEntry>, errors::ToolsError> {
-    let buf = BufReader::new(file);
-    buf.lines()
-        .map(|line| {
-            let line = line.map_err(errors::ToolsError::ReadLineError)?;
-            Ok(PgPassEntry::from(line))
+/// The hosts and ports of the postgres dialect datastores, as they are in their entries.
+fn get_satori_hosts<'a>(
+    datastores: impl Iterator<Item = &'a DatastoreInfo>,
+) -> Result<HashSet<(String, String)>, errors::ToolsError> {
+    let mut hosts = HashSet::new();
+    for datastore_info in datastores {
+        if !datastore_info.r#type.is_postgres_dialect() {
+            continue;
+        }
+        if let Some(port) = datastore_info
+            .port
+            .or_else(|| datastore_info.r#type.default_port())
+        {
+            hosts.insert((datastore_info.get_datastore_name()?, port.to_string()));
+        }
+    }
+    Ok(hosts)
+}
+
+/// The postgres dialect datastores, sorted by name, of the given types and whose name, or key
+/// with its id, matches one of the patterns. Empty types and patterns select all of them.
+pub(crate) fn get_postgres_datastores<'a>(
+    patterns: &[String],
+    types: &[DatastoreType],
+    datastores_info: &'a DatastoresInfo,
+) -> Result<Vec<(&'a str, &'a DatastoreInfo)>, errors::ToolsError> {
+    let patterns = patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern)
+                .map_err(|err| errors::ToolsError::InvalidDatastorePattern(pattern.clone(), err))
         })
-        .collect::<Result<HashSet<PgPassEntry>, errors::ToolsError>>()
+        .collect::<Result<Vec<Pattern>, errors::ToolsError>>()?;
+    let is_selected = |datastore_name: &str, datastore_info: &DatastoreInfo| {
+        let datastore_type = &datastore_info.r#type;
+        let name = datastore_name
+            .rsplit_once('#')
+            .map_or(datastore_name, |(name, _)| name);
+        datastore_type.is_postgres_dialect()
+            && (types.is_empty() || types.contains(datastore_type))
+            && (patterns.is_empty()
+                || patterns
+                    .iter()
+                    .any(|pattern| pattern.matches(name) || pattern.matches(datastore_name)))
+    };
+    let mut datastores = datastores_info
+        .datastores
+        .iter()
+        .filter(|(name, info)| is_selected(name, info))
+        .map(|(name, info)| (name.as_str(), info))
+        .collect::<Vec<(&str, &DatastoreInfo)>>();
+    datastores.sort_by_key(|(name, _)| *name);
+    Ok(datastores)
 }
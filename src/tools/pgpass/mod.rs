@@ -1,4 +1,5 @@
 pub mod data;
+pub mod file;
 pub mod flow;
 
 pub use data::PgPass;
@@ -0,0 +1,217 @@
+//! The libpq connection service file: `[service]` sections of `key=value` lines, lines starting
+//! with `#` are comments.
+
+use core::fmt;
+
+use crate::tools::{errors::ToolsError, pgpass::file::find_block};
+
+const BLOCK_BEGIN: &str = "# BEGIN satori services, regenerated by `satori pgservice`";
+const BLOCK_END: &str = "# END satori services";
+
+/// The lines of a service file. The satori services are kept in a marked block, which is
+/// regenerated on every run, every other line is written back as it was read.
+#[derive(Debug, Default)]
+pub struct PgServiceFile {
+    lines: Vec<String>,
+}
+
+/// A service section and its values, in the order they are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    pub name: String,
+    pub values: Vec<(String, String)>,
+}
+
+impl Service {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(value_key, _)| value_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+        for (key, value) in &self.values {
+            writeln!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PgServiceFile {
+    pub fn parse(content: &str) -> Self {
+        Self {
+            lines: content.lines().map(str::to_string).collect(),
+        }
+    }
+
+    /// The services of the satori block.
+    pub fn satori_services(&self) -> Result<Vec<Service>, ToolsError> {
+        Ok(match self.find_satori_block()? {
+            Some((start, end)) => parse_services(&self.lines[start..end]),
+            None => vec![],
+        })
+    }
+
+    /// The names of the services outside of the satori block.
+    pub fn other_service_names(&self) -> Result<Vec<String>, ToolsError> {
+        let lines = match self.find_satori_block()? {
+            Some((start, end)) => [&self.lines[..start], &self.lines[end..]].concat(),
+            None => self.lines.clone(),
+        };
+        Ok(lines
+            .iter()
+            .filter_map(|line| section_name(line))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Regenerates the satori block with the services, the lines outside of it aren't changed.
+    /// The block is added at the end of the file, so no service of the user continues into it.
+    pub fn replace_satori_block(&mut self, services: &[Service]) -> Result<(), ToolsError> {
+        let (start, end) = match self.find_satori_block()? {
+            Some(block) => block,
+            None => {
+                if self
+                    .lines
+                    .last()
+                    .is_some_and(|line| !line.trim().is_empty())
+                {
+                    self.lines.push(String::new());
+                }
+                (self.lines.len(), self.lines.len())
+            }
+        };
+        let mut block = vec![];
+        if !services.is_empty() {
+            block.push(BLOCK_BEGIN.to_string());
+            for (index, service) in services.iter().enumerate() {
+                if index > 0 {
+                    block.push(String::new());
+                }
+                block.extend(service.to_string().lines().map(str::to_string));
+            }
+            block.push(BLOCK_END.to_string());
+        }
+        self.lines.splice(start..end, block);
+        Ok(())
+    }
+
+    /// The range of lines of the satori block, including its markers.
+    fn find_satori_block(&self) -> Result<Option<(usize, usize)>, ToolsError> {
+        let lines = self.lines.iter().map(|line| Some(line.as_str()));
+        find_block(lines, BLOCK_BEGIN, BLOCK_END)
+            .map_err(|(line, reason)| ToolsError::InvalidPgServiceBlock { line, reason })
+    }
+}
+
+impl fmt::Display for PgServiceFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_services(lines: &[String]) -> Vec<Service> {
+    let mut services: Vec<Service> = vec![];
+    for line in lines {
+        let line = line.trim();
+        if let Some(name) = section_name(line) {
+            services.push(Service {
+                name: name.to_string(),
+                values: vec![],
+            });
+        } else if let (Some((key, value)), Some(service)) =
+            (line.split_once('='), services.last_mut())
+        {
+            if !line.starts_with('#') {
+                service
+                    .values
+                    .push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+    }
+    services
+}
+
+fn section_name(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(name: &str, host: &str) -> Service {
+        Service {
+            name: name.to_string(),
+            values: vec![("host".to_string(), host.to_string())],
+        }
+    }
+
+    #[test]
+    fn test_satori_block_is_regenerated() {
+        let content = "\
+# my services
+[local]
+host=localhost
+
+# BEGIN satori services, regenerated by `satori pgservice`
+[satori_old]
+host=old.example.com
+# END satori services
+
+[after]
+host=after.example.com
+";
+        let mut file = PgServiceFile::parse(content);
+        assert_eq!(
+            file.satori_services().unwrap(),
+            vec![service("satori_old", "old.example.com")]
+        );
+        assert_eq!(file.other_service_names().unwrap(), vec!["local", "after"]);
+
+        file.replace_satori_block(&[
+            service("satori_a", "a.example.com"),
+            service("satori_b", "b.example.com"),
+        ])
+        .unwrap();
+        assert_eq!(
+            file.to_string(),
+            "\
+# my services
+[local]
+host=localhost
+
+# BEGIN satori services, regenerated by `satori pgservice`
+[satori_a]
+host=a.example.com
+
+[satori_b]
+host=b.example.com
+# END satori services
+
+[after]
+host=after.example.com
+"
+        );
+    }
+
+    #[test]
+    fn test_satori_block_is_added_at_the_end() {
+        let mut file = PgServiceFile::parse("[local]\nhost=localhost");
+        file.replace_satori_block(&[service("satori_a", "a.example.com")])
+            .unwrap();
+        assert_eq!(
+            file.to_string(),
+            "[local]\nhost=localhost\n\n# BEGIN satori services, regenerated by `satori pgservice`\n[satori_a]\nhost=a.example.com\n# END satori services\n"
+        );
+    }
+}
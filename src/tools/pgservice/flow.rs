@@ -0,0 +1,286 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs, io,
+    path::Path,
+};
+
+use crate::{
+    helpers::{
+        datastores::{data::display_name, DatastoreInfo, DatastoresInfo},
+        dry_run::FileChange,
+    },
+    login,
+    tools::{errors, pgpass::flow::get_postgres_datastores},
+};
+
+use super::{
+    file::{PgServiceFile, Service},
+    PgService,
+};
+
+const SERVICE_NAME_PREFIX: &str = "satori_";
+
+/// Regenerates the satori services of the connection service file, the passwords are left to
+/// the pgpass file.
+pub async fn run<R>(params: PgService, user_input_stream: R) -> Result<(), errors::ToolsError>
+where
+    R: std::io::BufRead,
+{
+    let content = read_service_file(&params.path)?;
+    let mut service_file = PgServiceFile::parse(&content);
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+
+    let datastore_prefixes = get_datastore_prefixes(&datastores_info);
+    let selected_datastores =
+        get_postgres_datastores(&params.datastores, &params.types, &datastores_info)?;
+    let mut services = BTreeMap::new();
+    for (datastore_name, datastore_info) in &selected_datastores {
+        let host = datastore_info.get_datastore_name()?;
+        let port = datastore_info
+            .port
+            .or_else(|| datastore_info.r#type.default_port());
+        if datastore_info.databases.is_empty() {
+            log::warn!(
+                "Datastore {datastore_name} has no databases, not adding it to the service file"
+            );
+        }
+        for database in &datastore_info.databases {
+            let service_name = get_service_name(&datastore_prefixes[datastore_name], database);
+            if services.contains_key(&service_name) {
+                log::warn!("The service {service_name} of the database {database} of {datastore_name} is already used by another database, skipping it");
+                continue;
+            }
+            let mut values = vec![("host", host.clone())];
+            if let Some(port) = port {
+                values.push(("port", port.to_string()));
+            }
+            values.extend([
+                ("dbname", database.clone()),
+                ("user", credentials.username.clone()),
+                ("sslmode", "require".to_string()),
+                ("channel_binding", "disable".to_string()),
+            ]);
+            let values = values
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect();
+            log::info!("    {datastore_name} {database}: service={service_name}");
+            services.insert(
+                service_name.clone(),
+                Service {
+                    name: service_name,
+                    values,
+                },
+            );
+        }
+    }
+    if services.is_empty() {
+        log::warn!("No postgres datastores are available, go to the Satori Data Portal and verify that you have access to the relevant dataset. Also ensure that you have satori authentication for the datastore")
+    }
+
+    // The filters select the datastores which are refreshed, the services of the other
+    // datastores are kept, only the services of datastores which are gone are removed.
+    let satori_hosts = get_hosts(datastores_info.datastores.values())?;
+    let selected_hosts = get_hosts(selected_datastores.iter().map(|(_, info)| *info))?;
+    let existing_services = service_file.satori_services()?;
+    let generated = services.keys().cloned().collect::<HashSet<String>>();
+    for service in &existing_services {
+        let host = service.get("host").unwrap_or_default();
+        if satori_hosts.contains(host)
+            && !selected_hosts.contains(host)
+            && !services.contains_key(&service.name)
+        {
+            services.insert(service.name.clone(), service.clone());
+        }
+    }
+    let removed = existing_services
+        .iter()
+        .filter(|service| !services.contains_key(&service.name))
+        .map(|service| service.name.clone())
+        .collect::<Vec<String>>();
+    for service_name in &removed {
+        log::info!("Removing stale service: {service_name}");
+    }
+    for service_name in service_file.other_service_names()? {
+        if services.contains_key(&service_name) {
+            log::warn!(
+                "{}: the service {service_name} is also defined outside of the satori block, libpq uses the first of them",
+                params.path.display()
+            );
+        }
+    }
+    service_file.replace_satori_block(&services.into_values().collect::<Vec<Service>>())?;
+
+    if let Some(dry_run) = &params.dry_run {
+        let (mut updated, mut added): (Vec<String>, Vec<String>) =
+            generated.into_iter().partition(|service_name| {
+                existing_services
+                    .iter()
+                    .any(|service| service.name == *service_name)
+            });
+        updated.sort();
+        added.sort();
+        dry_run.record_file(FileChange {
+            path: params.path,
+            added,
+            updated,
+            removed,
+            ..Default::default()
+        });
+        return Ok(());
+    }
+
+    fs::write(&params.path, service_file.to_string())
+        .map_err(|err| errors::ToolsError::FailedToWriteToFile(err, params.path))?;
+    Ok(())
+}
+
+/// The content of the connection service file, empty when there is no file yet.
+fn read_service_file(path: &Path) -> Result<String, errors::ToolsError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            log::debug!(
+                "Service file {} not found, generating new file",
+                path.display()
+            );
+            Ok(String::new())
+        }
+        Err(err) => Err(errors::ToolsError::PgServiceReadError(
+            path.to_path_buf(),
+            err,
+        )),
+    }
+}
+
+/// The hosts of the postgres dialect datastores.
+fn get_hosts<'a>(
+    datastores: impl Iterator<Item = &'a DatastoreInfo>,
+) -> Result<HashSet<String>, errors::ToolsError> {
+    let mut hosts = HashSet::new();
+    for datastore_info in datastores {
+        if datastore_info.r#type.is_postgres_dialect() {
+            hosts.insert(datastore_info.get_datastore_name()?);
+        }
+    }
+    Ok(hosts)
+}
+
+/// The part of the service names which comes from the datastore, for every postgres dialect
+/// datastore by its key. It is the name of the datastore in the Satori data portal, with its id
+/// when another datastore gets the same part, so the names don't depend on the other datastores
+/// being in the cache or selected.
+fn get_datastore_prefixes(datastores_info: &DatastoresInfo) -> HashMap<&str, String> {
+    let datastores = datastores_info
+        .datastores
+        .iter()
+        .filter(|(_, info)| info.r#type.is_postgres_dialect())
+        .map(|(key, info)| (key.as_str(), info, sanitize(display_name(key, &info.id))))
+        .collect::<Vec<_>>();
+    datastores
+        .iter()
+        .map(|(key, info, name)| {
+            let collides = datastores
+                .iter()
+                .filter(|(_, _, other_name)| other_name == name)
+                .count()
+                > 1;
+            let prefix = if collides {
+                sanitize(&format!("{name}_{}", info.id))
+            } else {
+                name.clone()
+            };
+            (*key, prefix)
+        })
+        .collect()
+}
+
+/// `satori_<datastore>_<database>`, where the datastore part comes from
+/// [`get_datastore_prefixes`].
+fn get_service_name(datastore_prefix: &str, database: &str) -> String {
+    format!(
+        "{SERVICE_NAME_PREFIX}{}",
+        sanitize(&format!("{datastore_prefix}_{database}"))
+    )
+}
+
+/// Lower case, with every run of characters which aren't ascii letters or digits replaced by a
+/// single `_`.
+fn sanitize(name: &str) -> String {
+    let mut sanitized = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            sanitized.push(c.to_ascii_lowercase());
+        } else if !sanitized.is_empty() && !sanitized.ends_with('_') {
+            sanitized.push('_');
+        }
+    }
+    sanitized.trim_end_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::satori_console::DatastoreType;
+
+    use super::*;
+
+    fn datastores_info(datastores: &[(&str, &str)]) -> DatastoresInfo {
+        DatastoresInfo {
+            account_id: String::new(),
+            datastores: datastores
+                .iter()
+                .map(|(key, id)| {
+                    let info = DatastoreInfo {
+                        id: id.to_string(),
+                        satori_host: format!("{id}.example.com"),
+                        databases: vec![],
+                        port: None,
+                        r#type: DatastoreType::Postgresql,
+                        deployment_type: None,
+                    };
+                    (key.to_string(), info)
+                })
+                .collect(),
+            unusable_datastores: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_service_name() {
+        assert_eq!(
+            get_service_name("prod", "analytics"),
+            "satori_prod_analytics"
+        );
+        assert_eq!(
+            get_service_name("prod_sales", "Q3-orders."),
+            "satori_prod_sales_q3_orders"
+        );
+    }
+
+    #[test]
+    fn test_datastore_prefixes() {
+        let single = datastores_info(&[("Prod Sales", "a1b2"), ("analytics", "e5f6")]);
+        assert_eq!(
+            get_datastore_prefixes(&single),
+            HashMap::from([
+                ("Prod Sales", "prod_sales".to_string()),
+                ("analytics", "analytics".to_string())
+            ])
+        );
+
+        let colliding = datastores_info(&[
+            ("Prod Sales#a1b2", "a1b2"),
+            ("prod-sales", "c3d4"),
+            ("analytics", "e5f6"),
+        ]);
+        assert_eq!(
+            get_datastore_prefixes(&colliding),
+            HashMap::from([
+                ("Prod Sales#a1b2", "prod_sales_a1b2".to_string()),
+                ("prod-sales", "prod_sales_c3d4".to_string()),
+                ("analytics", "analytics".to_string()),
+            ])
+        );
+    }
+}
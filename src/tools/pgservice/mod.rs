@@ -0,0 +1,6 @@
+pub mod data;
+pub mod file;
+pub mod flow;
+
+pub use data::PgService;
+pub use flow::run;
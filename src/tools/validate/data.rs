@@ -0,0 +1,7 @@
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct Validate {
+    /// Validate only this tools file, instead of the built-in and the user tools.
+    pub path: Option<PathBuf>,
+}
@@ -0,0 +1,261 @@
+use std::{collections::HashSet, io::Write, path::Path};
+
+use crate::{
+    helpers::{
+        default_app_folder,
+        satori_console::DatastoreType,
+        tools::{self, file, template, CliArgType, Tool, CONTEXT_VARIABLES, RESERVED_ARG_NAMES},
+    },
+    tools::errors::ToolsError,
+};
+
+use super::Validate;
+
+const BUILT_IN_SOURCE: &str = "built-in tools";
+
+pub fn run<W>(params: &Validate, writer: &mut W) -> Result<(), ToolsError>
+where
+    W: Write,
+{
+    let sources = match &params.path {
+        Some(path) => vec![read_source(path)?],
+        None => {
+            let mut sources = vec![(BUILT_IN_SOURCE.to_string(), tools::TOOLS_DATA.to_string())];
+            match default_app_folder::get() {
+                Ok(satori_folder_path) => {
+                    for path in file::get_user_tools_files(&satori_folder_path) {
+                        sources.push(read_source(&path)?);
+                    }
+                }
+                Err(err) => log::debug!("Skipping user tools: {err}"),
+            }
+            sources
+        }
+    };
+
+    let mut problems_count = 0;
+    for (source, content) in &sources {
+        let problems = validate(content);
+        if problems.is_empty() {
+            writeln!(writer, "{source}: ok").map_err(ToolsError::WriteError)?;
+        }
+        for problem in &problems {
+            writeln!(writer, "{source}: {problem}").map_err(ToolsError::WriteError)?;
+        }
+        problems_count += problems.len();
+    }
+
+    if problems_count > 0 {
+        return Err(ToolsError::InvalidTools(problems_count));
+    }
+    Ok(())
+}
+
+fn read_source(path: &Path) -> Result<(String, String), ToolsError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|err| ToolsError::FailedToReadToolsFile(err, path.to_path_buf()))?;
+    Ok((path.display().to_string(), content))
+}
+
+/// Returns the problems found in the tools definitions file content.
+fn validate(content: &str) -> Vec<String> {
+    let tools = match serde_yaml::from_str::<Vec<Tool>>(content) {
+        Ok(tools) => tools,
+        Err(err) => return vec![format!("invalid tools file: {err}")],
+    };
+
+    let mut problems = vec![];
+    let mut names = HashSet::new();
+    for tool in &tools {
+        if !names.insert(tool.name.as_str()) {
+            problems.push(format!("tool {} is defined more than once", tool.name));
+        }
+        problems.extend(
+            validate_cli_args(tool)
+                .into_iter()
+                .chain(validate_types(tool))
+                .chain(validate_templates(tool))
+                .map(|problem| format!("tool {}: {problem}", tool.name)),
+        );
+    }
+    problems
+}
+
+fn validate_cli_args(tool: &Tool) -> Vec<String> {
+    let mut problems = vec![];
+    let mut names = HashSet::new();
+    for cli_arg in &tool.cli_args {
+        let name = &cli_arg.name;
+        if !names.insert(cli_arg.context_name()) {
+            problems.push(format!("argument {name} is defined more than once"));
+        }
+        if RESERVED_ARG_NAMES.contains(&name.as_str()) {
+            problems.push(format!(
+                "argument {name} conflicts with a built-in argument"
+            ));
+        } else if name != "database" && CONTEXT_VARIABLES.contains(&cli_arg.context_name().as_str())
+        {
+            problems.push(format!(
+                "argument {name} hides the {name} variable of the connection details"
+            ));
+        }
+        if cli_arg.short == Some('h') {
+            problems.push(format!("argument {name} can't use -h, it is used for help"));
+        }
+        if cli_arg.r#type == CliArgType::Flag {
+            if cli_arg.default.is_some() || !cli_arg.choices.is_empty() {
+                problems.push(format!("flag {name} can't have a default or choices"));
+            }
+        } else if let Some(default) = &cli_arg.default {
+            if !cli_arg.choices.is_empty() && !cli_arg.choices.contains(default) {
+                problems.push(format!(
+                    "default {default} of argument {name} isn't one of its choices"
+                ));
+            }
+        }
+    }
+    problems
+}
+
+fn validate_types(tool: &Tool) -> Vec<String> {
+    let mut problems = vec![];
+    if tool
+        .supported_types
+        .iter()
+        .chain(&tool.default_for)
+        .any(|datastore_type| datastore_type == &DatastoreType::Unknown)
+    {
+        problems.push("unknown datastore type in supported_types or default_for".to_string());
+    }
+    for datastore_type in &tool.default_for {
+        if !tool.supports(datastore_type) {
+            problems.push(format!(
+                "default_for {datastore_type:?} isn't one of the supported_types"
+            ));
+        }
+    }
+    problems
+}
+
+fn validate_templates(tool: &Tool) -> Vec<String> {
+    let known_variables = CONTEXT_VARIABLES
+        .iter()
+        .chain(&template::FUNCTIONS)
+        .map(|variable| variable.to_string())
+        .chain(tool.cli_args.iter().map(|cli_arg| cli_arg.context_name()))
+        .collect::<Vec<String>>();
+    let env = template::get_environment();
+    let mut problems = vec![];
+    for (name, source) in tool.get_templates() {
+        let template = match env.template_from_named_str(&name, source) {
+            Ok(template) => template,
+            Err(err) => {
+                problems.push(format!("invalid template `{source}`: {err}"));
+                continue;
+            }
+        };
+        let mut undefined = template
+            .undeclared_variables(false)
+            .into_iter()
+            .filter(|variable| !known_variables.contains(variable))
+            .collect::<Vec<String>>();
+        undefined.sort();
+        for variable in undefined {
+            problems.push(format!(
+                "undefined variable {variable} in {name}, expected one of: {}",
+                known_variables.join(", ")
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_tools_are_valid() {
+        assert!(validate(tools::TOOLS_DATA).is_empty());
+    }
+
+    #[test]
+    fn test_validate_problems() {
+        let problems = validate(
+            "- name: psql
+  command: psql
+  command_args: ['{{ host }', '{{ hostname }}']
+- name: psql
+  command: psql
+  command_args: -h {{ host }}
+  env:
+    - name: PGPASSWORD
+      value: '{{ pass }}'
+",
+        );
+        assert_eq!(problems.len(), 4);
+        assert!(problems[0].starts_with("tool psql: invalid template `{{ host }`: syntax error"));
+        assert!(problems[0].contains("(in command_args[0]:1)"));
+        assert!(
+            problems[1].starts_with("tool psql: undefined variable hostname in command_args[1]")
+        );
+        assert_eq!(problems[2], "tool psql is defined more than once");
+        assert!(problems[3].starts_with("tool psql: undefined variable pass in env.PGPASSWORD"));
+    }
+
+    #[test]
+    fn test_validate_cli_args() {
+        let problems = validate(
+            "- name: mongosh
+  command: mongosh
+  command_args: ['{{ host }}', '--readPreference={{ read_preference }}', '{{ quiet }}']
+  cli_args:
+    - name: read-preference
+      help: Read preference
+      type: option
+      default: nearest
+      choices: [primary, secondary]
+    - name: quiet
+      help: Quiet
+      type: flag
+      short: h
+    - name: user
+      help: User
+",
+        );
+        assert_eq!(
+            problems,
+            vec![
+                "tool mongosh: default nearest of argument read-preference isn't one of its choices",
+                "tool mongosh: argument quiet can't use -h, it is used for help",
+                "tool mongosh: argument user hides the user variable of the connection details",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_types() {
+        let problems = validate(
+            "- name: psql
+  command: psql
+  command_args: -h {{ host }}
+  supported_types: [POSTGRESQL, POSTGRES]
+  default_for: [REDSHIFT]
+",
+        );
+        assert_eq!(
+            problems,
+            vec![
+                "tool psql: unknown datastore type in supported_types or default_for",
+                "tool psql: default_for Redshift isn't one of the supported_types",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_yaml() {
+        let problems = validate("- name: psql\n  command_args: -h {{ host }}\n");
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("invalid tools file: "));
+    }
+}
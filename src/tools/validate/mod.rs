@@ -0,0 +1,5 @@
+pub mod data;
+pub mod flow;
+
+pub use data::Validate;
+pub use flow::run;
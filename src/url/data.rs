@@ -0,0 +1,13 @@
+use crate::{helpers::connection_url::UrlFormat, login::Login};
+
+#[derive(Debug)]
+pub struct Url {
+    pub login: Login,
+    pub datastore_name: String,
+    /// Selects between datastores sharing the same name.
+    pub datastore_id: Option<String>,
+    pub database: Option<String>,
+    /// When not set, the common format of the datastore type is used
+    pub format: Option<UrlFormat>,
+    pub redact: bool,
+}
@@ -0,0 +1,16 @@
+use crate::{
+    helpers::{connection_url::ConnectionUrlError, datastores::errors::ResolveDatastoreError},
+    login::errors::LoginError,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum UrlError {
+    #[error("Failed to login: {0}")]
+    LoginError(#[from] LoginError),
+    #[error("{0}")]
+    ResolveDatastoreError(#[from] ResolveDatastoreError),
+    #[error("{0}")]
+    ConnectionUrlError(#[from] ConnectionUrlError),
+    #[error("Failed to write the url: {0}")]
+    WriteError(std::io::Error),
+}
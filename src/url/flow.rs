@@ -0,0 +1,33 @@
+use zeroize::Zeroizing;
+
+use crate::{
+    helpers::connection_url::{ConnectionDetails, UrlFormat},
+    login,
+};
+
+use super::{errors::UrlError, Url};
+
+pub async fn run<R, W>(params: Url, user_input_stream: R, writer: &mut W) -> Result<(), UrlError>
+where
+    R: std::io::BufRead,
+    W: std::io::Write,
+{
+    let (credentials, datastores_info) =
+        login::run_with_file(&params.login, user_input_stream).await?;
+    let datastore_info =
+        datastores_info.resolve(&params.datastore_name, params.datastore_id.as_deref())?;
+    let format = params
+        .format
+        .unwrap_or_else(|| UrlFormat::default_for(&datastore_info.r#type));
+
+    let connection_details = ConnectionDetails {
+        datastore_info,
+        username: &credentials.username,
+        password: &credentials.password,
+        database: params.database.as_deref(),
+        redact: params.redact,
+    };
+    let url = Zeroizing::new(connection_details.to_url(format)?);
+    writeln!(writer, "{}", url.as_str()).map_err(UrlError::WriteError)?;
+    Ok(())
+}
@@ -2,12 +2,17 @@
 
 use std::path::{Path, PathBuf};
 
+use chrono::SecondsFormat;
 use httpmock::MockServer;
 use ini::Ini;
 use satori_cli::{
     helpers::datastores::DatastoresInfo,
     login::{data::Credentials, Login, LoginBuilder},
-    tools::aws::{self, data::AwsBuilder, Aws},
+    tools::aws::{
+        self,
+        data::{AwsBuilder, AwsCredentialProcessBuilder},
+        Aws,
+    },
 };
 use tempfile::TempDir;
 use test_utils::{
@@ -222,6 +227,160 @@ async fn test_aws_stale_profiles_removed() {
     );
 }
 
+/// Validates that the profiles get a credential process instead of the credentials, and that the
+/// satori credentials already written are removed
+#[tokio::test]
+async fn test_aws_credential_process_profiles() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("aws_datastores.json");
+    let old_config = read_ini_file(AWS_CREDENTIALS_DIR, "satori_config");
+    let old_credentials = read_ini_file(AWS_CREDENTIALS_DIR, "expired_credentials");
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    write_aws_temp_dir(&temp_dir, old_config, old_credentials);
+
+    run_aws_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &get_access_details_db_empty_response_path(),
+        AwsBuilder::default().credential_process(true),
+    )
+    .await;
+
+    let actual_aws_credentials = read_actual_aws_file(&temp_dir, "credentials");
+    let actual_aws_config = read_actual_aws_file(&temp_dir, "config");
+    for (profile_name, datastore_name) in [
+        (ATHENA_PROFILE, ATHENA_DATASTORE_NAME),
+        (S3_PROFILE, S3_DATASTORE_NAME),
+    ] {
+        assert!(actual_aws_credentials.section(Some(profile_name)).is_none());
+        let config_section = actual_aws_config
+            .section(Some(get_name_with_profile(profile_name)))
+            .unwrap();
+        assert_eq!(
+            config_section.get("credential_process").unwrap(),
+            format!("{} aws credential-process {datastore_name}", satori_path())
+        );
+        assert!(config_section.get("endpoint_url").is_some());
+    }
+}
+
+/// Validates that a datastore name with quotes and backslashes is written to the config as it is
+/// quoted, without the escapes of the ini format
+#[tokio::test]
+async fn test_aws_credential_process_quoted_name() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("aws_quoted_name_datastores.json");
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    run_aws_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &get_access_details_db_empty_response_path(),
+        AwsBuilder::default().credential_process(true),
+    )
+    .await;
+
+    let actual_aws_config = std::fs::read_to_string(temp_dir.path().join("config")).unwrap();
+    let expected_line = format!(
+        r#"credential_process={} aws credential-process "sales \"eu\" \\ data""#,
+        satori_path()
+    );
+    assert!(
+        actual_aws_config.lines().any(|line| line == expected_line),
+        "{actual_aws_config}"
+    );
+}
+
+/// Validates that the command of the credential process, split like the AWS SDKs do, runs satori
+/// by its path with the key of the datastore, which resolves to the datastore of the profile when
+/// several datastores have the same name
+#[tokio::test]
+async fn test_aws_credential_process_resolves_datastore() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("aws_duplicate_name_datastores.json");
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    run_aws_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &get_access_details_db_empty_response_path(),
+        AwsBuilder::default().credential_process(true),
+    )
+    .await;
+
+    let actual_aws_config = read_actual_aws_file(&temp_dir, "config");
+    let mut datastore_names = Vec::new();
+    for (_, properties) in actual_aws_config.iter() {
+        let Some(command) = properties.get("credential_process") else {
+            continue;
+        };
+        let args = shlex::split(command).unwrap();
+        let [path, aws, credential_process, datastore_name] = args.as_slice() else {
+            panic!("unexpected credential process: {command}");
+        };
+        assert_eq!(
+            [path.as_str(), aws, credential_process],
+            [satori_path().as_str(), "aws", "credential-process"]
+        );
+        let datastore_info = datastores_info.resolve(datastore_name, None).unwrap();
+        assert_eq!(
+            properties.get("endpoint_url").unwrap(),
+            format!("https://{}", datastore_info.satori_host)
+        );
+        datastore_names.push(datastore_name.clone());
+    }
+    datastore_names.sort();
+    assert_eq!(datastore_names, vec!["sales#a1b2", "sales#c3d4"]);
+
+    let server = MockServer::start();
+    let login = build_login(LoginBuilder::default(), &server.base_url(), &temp_dir);
+    let params = AwsCredentialProcessBuilder::default()
+        .login(login)
+        .datastore_name(datastore_names[0].clone())
+        .build()
+        .unwrap();
+    aws::credential_process(&params, &get_encoded_challenge()[..], &mut Vec::new())
+        .await
+        .unwrap();
+}
+
+/// Validates the credentials printed for the AWS credential process
+#[tokio::test]
+async fn test_aws_credential_process_output() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("aws_datastores.json");
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    let server = MockServer::start();
+    let login = build_login(LoginBuilder::default(), &server.base_url(), &temp_dir);
+    let params = AwsCredentialProcessBuilder::default()
+        .login(login)
+        .datastore_name(ATHENA_DATASTORE_NAME.to_string())
+        .build()
+        .unwrap();
+    let mut output = Vec::new();
+    aws::credential_process(&params, &get_encoded_challenge()[..], &mut output)
+        .await
+        .unwrap();
+
+    let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        output,
+        serde_json::json!({
+            "Version": 1,
+            "AccessKeyId": credentials.username,
+            "SecretAccessKey": credentials.password.expose(),
+            "Expiration": credentials.expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        })
+    );
+}
+
 async fn run_aws_with_server_assert_no_calls_to_server(
     temp_dir: &TempDir,
     datastores_info_file_path: &Path,
@@ -306,6 +465,14 @@ pub fn read_ini_file(file_path: &str, filename: &str) -> Ini {
     Ini::load_from_file(file_path).unwrap()
 }
 
+/// The credential process runs the executable which wrote it, the test binary.
+fn satori_path() -> String {
+    std::env::current_exe()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
 fn get_name_with_profile(profile_name: &str) -> String {
     format!("profile {}", profile_name)
 }
@@ -346,14 +513,14 @@ fn validates_aws_files(
     let actual_athena_password = athena_values.get("aws_secret_access_key").unwrap();
 
     assert_eq!(actual_athena_username, credentials.username);
-    assert_eq!(actual_athena_password, credentials.password);
+    assert_eq!(actual_athena_password, credentials.password.expose());
 
     let s3_values = actual_aws_credentials.section(Some(S3_PROFILE)).unwrap();
     let actual_s3_username = s3_values.get("aws_access_key_id").unwrap();
     let actual_s3_password = s3_values.get("aws_secret_access_key").unwrap();
 
     assert_eq!(actual_s3_username, credentials.username);
-    assert_eq!(actual_s3_password, credentials.password);
+    assert_eq!(actual_s3_password, credentials.password.expose());
 
     let athena_section = actual_aws_config
         .section(Some(expected_athena_creds_section.clone()))
@@ -0,0 +1,170 @@
+#![warn(clippy::all)]
+
+mod test_utils;
+
+use httpmock::MockServer;
+use satori_cli::{
+    login::LoginBuilder,
+    run::{connect, errors::RunError, CommandStatus, ConnectBuilder, DynamicToolBuilder},
+};
+use test_utils::{
+    constants::{ACCESS_TOKEN, CODE_CHALLENGE, SATORI_ACCOUNT_ID, SATORI_USER_ID},
+    credentials::{get_old_credentials_expire_two_hours, write_credentials_temp_dir},
+    datastores::{get_mock_datastores, write_datastores_temp_dir},
+    login_helpers::build_login,
+    mock_command_executer::MockCommandExecuter,
+    mock_server::{
+        get_access_details_db_empty_response_path, get_encoded_challenge, run_server_no_asserts,
+    },
+    temp_dir,
+};
+
+/// Postgres datastores are connected with psql
+#[tokio::test]
+async fn test_connect_postgres() {
+    let credentials = get_old_credentials_expire_two_hours();
+    let mut mock_executer = MockCommandExecuter::new("psql".to_string());
+    mock_executer.expected_args = vec![
+        "-h".to_string(),
+        "postgres.example.com".to_string(),
+        "-U".to_string(),
+        credentials.username.clone(),
+        "-d".to_string(),
+        "customers".to_string(),
+        "--port".to_string(),
+        "5432".to_string(),
+    ];
+    mock_executer.expected_envs = vec![
+        (
+            "PGPASSWORD".to_string(),
+            credentials.password.expose().to_string(),
+        ),
+        ("PGCHANNELBINDING".to_string(), "disable".to_string()),
+    ];
+
+    run_connect(
+        "psql_datastores.json",
+        "postgres",
+        Some("customers"),
+        mock_executer,
+    )
+    .await
+    .unwrap();
+    MockCommandExecuter::assert();
+}
+
+/// Mongo datastores are connected with mongosh
+#[tokio::test]
+async fn test_connect_mongo() {
+    let credentials = get_old_credentials_expire_two_hours();
+    let mut mock_executer = MockCommandExecuter::new("mongosh".to_string());
+    mock_executer.expected_args = vec![
+        "--nodb".to_string(),
+        "--shell".to_string(),
+        format!(
+            "db = connect('mongodb+srv://{}:{}@mongo.example.com/?authSource=admin');",
+            credentials.username,
+            credentials.password.expose()
+        ),
+    ];
+    mock_executer.file_args = vec![2];
+
+    run_connect(
+        "mongo_datastores.json",
+        "mongo_datastore",
+        None,
+        mock_executer,
+    )
+    .await
+    .unwrap();
+    MockCommandExecuter::assert();
+}
+
+/// psql requires a database, connect doesn't guess one
+#[tokio::test]
+async fn test_connect_postgres_without_database() {
+    let mock_executer = MockCommandExecuter::new("psql".to_string());
+
+    let result = run_connect("psql_datastores.json", "postgres", None, mock_executer).await;
+    assert!(matches!(
+        result,
+        Err(RunError::MissingToolArgument { tool, argument }) if tool == "psql" && argument == "database"
+    ));
+}
+
+/// A tool isn't run against a datastore type it doesn't support
+#[tokio::test]
+async fn test_run_unsupported_datastore_type() {
+    let temp_dir = temp_dir::generate();
+    write_credentials_temp_dir(&get_old_credentials_expire_two_hours(), &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores("mongo_datastores.json"), &temp_dir);
+
+    let server = MockServer::start();
+    let params = DynamicToolBuilder::default()
+        .tool("psql".to_string())
+        .login(build_login(
+            LoginBuilder::default(),
+            &server.base_url(),
+            &temp_dir,
+        ))
+        .datastore_name("mongo_datastore".to_string())
+        .database(Some("customers".to_string()))
+        .additional_args(vec![])
+        .build()
+        .unwrap();
+    let encoded_challenge = get_encoded_challenge();
+    let result = satori_cli::run::dynamic_tools::run(
+        params,
+        &encoded_challenge[..],
+        MockCommandExecuter::new("psql".to_string()),
+    )
+    .await;
+
+    let err = result.unwrap_err();
+    assert!(matches!(err, RunError::UnsupportedDatastoreType { .. }));
+    assert_eq!(
+        err.to_string(),
+        "Tool psql doesn't support Mongo datastores, it supports: [Postgresql, Redshift, CockroachDb, Greenplum]"
+    );
+}
+
+async fn run_connect(
+    datastores_file_name: &str,
+    datastore_name: &str,
+    database: Option<&str>,
+    mock_executer: MockCommandExecuter,
+) -> Result<CommandStatus, RunError> {
+    let temp_dir = temp_dir::generate();
+    write_credentials_temp_dir(&get_old_credentials_expire_two_hours(), &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores(datastores_file_name), &temp_dir);
+
+    let server = MockServer::start();
+    let (server_jwt_mock, user_info_mock, database_credentials_mock, datastores_mock) =
+        run_server_no_asserts(
+            &server,
+            &get_access_details_db_empty_response_path(),
+            CODE_CHALLENGE,
+            ACCESS_TOKEN.to_string(),
+            SATORI_USER_ID.to_string(),
+            SATORI_ACCOUNT_ID.to_string(),
+        )
+        .await;
+    let params = ConnectBuilder::default()
+        .login(build_login(
+            LoginBuilder::default(),
+            &server.base_url(),
+            &temp_dir,
+        ))
+        .datastore_name(datastore_name.to_string())
+        .database(database.map(str::to_string))
+        .build()
+        .unwrap();
+    let encoded_challenge = get_encoded_challenge();
+    let result = connect::run(params, &encoded_challenge[..], mock_executer).await;
+
+    server_jwt_mock.assert_hits(0);
+    user_info_mock.assert_hits(0);
+    database_credentials_mock.assert_hits(0);
+    datastores_mock.assert_hits(0);
+    result
+}
@@ -2,13 +2,19 @@
 
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use httpmock::MockServer;
 
 use satori_cli::login::data::Credentials;
 use satori_cli::login::{Login, LoginBuilder};
-use satori_cli::run::{dbt, dbt::Profiles, Dbt, DbtBuilder};
+use satori_cli::run::{dbt, errors::RunError, Dbt, DbtBuilder};
+use satori_cli::tools::{
+    self,
+    dbt::{data::DbtInitBuilder, DbtInit},
+    errors::ToolsError,
+};
 use tempfile::TempDir;
 use test_utils::mock_command_executer::MockCommandExecuter;
 use test_utils::{
@@ -43,7 +49,7 @@ async fn test_dbt_credentials_from_file() {
     let credentials = get_old_credentials_expire_two_hours();
     let datastores_info = get_mock_datastores("postgres_datastores.json");
     let datastores_entries_response_path = get_access_details_db_empty_response_path();
-    let mock_executer = build_mock_executer(&temp_dir, &credentials);
+    let mock_executer = build_mock_executer(&credentials, "expected_generated_profiles.yml");
 
     let profiles = read_dbt_profiles_file(DBT_DIR, "profiles.yml");
 
@@ -54,7 +60,7 @@ async fn test_dbt_credentials_from_file() {
     run_dbt_with_server_assert_no_calls_to_server(
         &temp_dir,
         &datastores_entries_response_path,
-        get_dbt_builder("dev".to_string(), "satori_cli_test_profile".to_owned()),
+        get_dbt_builder("dev".to_string()),
         mock_executer,
     )
     .await;
@@ -75,7 +81,7 @@ async fn test_dbt_credentials_from_server() {
     let new_credentials = get_new_credentials_expire_two_hours();
     let datastores_info = get_mock_datastores("postgres_datastores.json");
     let datastores_entries_response_path = get_access_details_db_empty_response_path();
-    let mock_executer = build_mock_executer(&temp_dir, &new_credentials);
+    let mock_executer = build_mock_executer(&new_credentials, "expected_generated_profiles.yml");
 
     let profiles = read_dbt_profiles_file(DBT_DIR, "profiles.yml");
 
@@ -86,7 +92,7 @@ async fn test_dbt_credentials_from_server() {
     run_dbt_with_server_assert_credentials(
         &temp_dir,
         &datastores_entries_response_path,
-        get_dbt_builder("dev".to_string(), "satori_cli_test_profile".to_owned()),
+        get_dbt_builder("dev".to_string()),
         mock_executer,
     )
     .await;
@@ -99,7 +105,8 @@ async fn test_dbt_credentials_from_server() {
     assert_no_backup(&temp_dir);
 }
 
-/// A profile with some username and password exist, we replace them with the satori env var
+/// A profile with some username and password exist, the generated profiles replace them with the
+/// satori env vars
 #[tokio::test]
 async fn test_dbt_replace_user_password() {
     let temp_dir = temp_dir::generate();
@@ -107,7 +114,7 @@ async fn test_dbt_replace_user_password() {
     let new_credentials = get_new_credentials_expire_two_hours();
     let datastores_info = get_mock_datastores("postgres_datastores.json");
     let datastores_entries_response_path = get_access_details_db_empty_response_path();
-    let mock_executer = build_mock_executer(&temp_dir, &new_credentials);
+    let mock_executer = build_mock_executer(&new_credentials, "expected_generated_profiles.yml");
 
     let profiles = read_dbt_profiles_file(DBT_DIR, "profiles_with_creds.yml");
 
@@ -118,18 +125,16 @@ async fn test_dbt_replace_user_password() {
     run_dbt_with_server_assert_credentials(
         &temp_dir,
         &datastores_entries_response_path,
-        get_dbt_builder("dev".to_string(), "satori_cli_test_profile".to_owned()),
+        get_dbt_builder("dev".to_string()),
         mock_executer,
     )
     .await;
 
     let actual_dbt = read_actual_dbt_file(&temp_dir);
-    let expected_dbt = read_dbt_profiles_file(DBT_DIR, "profiles.yml");
 
-    // validate we don't change the profiles file
-    assert_eq!(actual_dbt, expected_dbt);
-    // Validate we backup the original profiles file
-    assert_backup(&profiles, &temp_dir);
+    // validate we don't change the profiles file, dbt reads a generated copy
+    assert_eq!(actual_dbt, profiles);
+    assert_no_backup(&temp_dir);
 }
 
 async fn run_dbt_with_server_assert_no_calls_to_server(
@@ -179,6 +184,462 @@ async fn run_dbt_with_server_assert_credentials(
     datastores_mock.assert_hits(0);
 }
 
+/// A target naming a Satori datastore gets its host and port.
+#[tokio::test]
+async fn test_dbt_satori_datastore_target() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    let mut mock_executer = build_mock_executer(
+        &credentials,
+        "expected_generated_profiles_satori_datastore.yml",
+    );
+    // dbt-redshift doesn't use libpq
+    mock_executer.expected_envs.remove(0);
+
+    let profiles = read_dbt_profiles_file(DBT_DIR, "profiles_satori_datastore.yml");
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    run_dbt_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        get_dbt_builder("dev".to_string()),
+        mock_executer,
+    )
+    .await;
+}
+
+/// An Athena target gets the Satori endpoint and keys, instead of its AWS profile.
+#[tokio::test]
+async fn test_dbt_athena_target() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("athena_only_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    let mut mock_executer =
+        build_mock_executer(&credentials, "expected_generated_profiles_athena.yml");
+    mock_executer.expected_envs[0] = (
+        "AWS_ENDPOINT_URL".to_owned(),
+        "https://athena.example.com".to_owned(),
+    );
+
+    let profiles = read_dbt_profiles_file(DBT_DIR, "profiles_athena.yml");
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    run_dbt_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        get_dbt_builder("dev".to_string()),
+        mock_executer,
+    )
+    .await;
+}
+
+/// A Greenplum target keeps the env var credentials, replacing the `pass` alias.
+#[tokio::test]
+async fn test_dbt_greenplum_target() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    let mock_executer =
+        build_mock_executer(&credentials, "expected_generated_profiles_greenplum.yml");
+
+    let profiles = read_dbt_profiles_file(DBT_DIR, "profiles_greenplum.yml");
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    run_dbt_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        get_dbt_builder("dev".to_string()),
+        mock_executer,
+    )
+    .await;
+}
+
+/// A target of an adapter Satori doesn't support fails before the login.
+#[tokio::test]
+async fn test_dbt_unsupported_adapter() {
+    let temp_dir = temp_dir::generate();
+    let profiles: dbt::Profiles = serde_yaml::from_str(
+        "satori_cli_test_profile:
+  target: dev
+  outputs:
+    dev:
+      type: snowflake
+      account: abc123
+      database: analytics
+",
+    )
+    .unwrap();
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    let login_params = build_login(LoginBuilder::default(), "http://localhost", &temp_dir);
+    let dbt_params = build_dbt(get_dbt_builder("dev".to_string()), login_params, &temp_dir);
+    let err = dbt::run(
+        dbt_params,
+        &b""[..],
+        MockCommandExecuter::new("dbt".to_owned()),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        matches!(err, RunError::DbtUnsupportedAdapter { .. }),
+        "{err}"
+    );
+}
+
+/// A target whose host isn't a Satori datastore doesn't get the Satori credentials.
+#[tokio::test]
+async fn test_dbt_non_satori_target() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let profiles = read_dbt_profiles_file(DBT_DIR, "profiles.yml");
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    let login_params = build_login(LoginBuilder::default(), "http://localhost", &temp_dir);
+    let dbt_params = build_dbt(get_dbt_builder("prod".to_string()), login_params, &temp_dir);
+    let err = dbt::run(
+        dbt_params,
+        &b""[..],
+        MockCommandExecuter::new("dbt".to_owned()),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(err, RunError::DbtTargetNotSatori { .. }), "{err}");
+    assert_eq!(read_actual_dbt_file(&temp_dir), profiles);
+}
+
+/// A target whose host serves several datastores must set the port of one of them, a quoted
+/// port is rendered like any other value.
+#[tokio::test]
+async fn test_dbt_shared_host_target() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("shared_host_datastores.json");
+    let profiles: dbt::Profiles = serde_yaml::from_str(
+        "satori_cli_test_profile:
+  target: dev
+  outputs:
+    dev:
+      type: postgres
+      host: shared.example.com
+      dbname: postgres
+    quoted_port:
+      type: redshift
+      host: shared.example.com
+      port: \"{{ env_var('SATORI_TEST_UNSET_PORT', '5432') }}\"
+      dbname: postgres
+",
+    )
+    .unwrap();
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    let run_target = |target: &str| {
+        let login_params = build_login(LoginBuilder::default(), "http://localhost", &temp_dir);
+        let dbt_params = build_dbt(get_dbt_builder(target.to_string()), login_params, &temp_dir);
+        dbt::run(
+            dbt_params,
+            &b""[..],
+            MockCommandExecuter::new("dbt".to_owned()),
+        )
+    };
+
+    let err = run_target("dev").await.unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            RunError::DbtTargetAmbiguousHost { datastores, .. }
+                if datastores == "postgres (Postgresql), redshift (Redshift)"
+        ),
+        "{err}"
+    );
+
+    // The port selects the PostgreSQL datastore, which the redshift adapter can't connect to.
+    let err = run_target("quoted_port").await.unwrap_err();
+    assert!(
+        matches!(err, RunError::DbtDatastoreTypeMismatch { .. }),
+        "{err}"
+    );
+}
+
+/// Runs the satori binary in debug mode, the logs must not contain the satori password or the
+/// password of the profile it rewrites.
+#[test]
+fn test_dbt_debug_does_not_print_passwords() {
+    let home_dir = temp_dir::generate();
+    let satori_dir = home_dir.path().join(".satori");
+    fs::create_dir(&satori_dir).unwrap();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    fs::write(
+        satori_dir.join("credentials.json"),
+        serde_json::to_string(&credentials).unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        satori_dir.join("datastores.json"),
+        serde_json::to_string(&datastores_info).unwrap(),
+    )
+    .unwrap();
+    for file_name in ["dbt_project.yml", "profiles_with_creds.yml"] {
+        fs::copy(
+            Path::new(DBT_DIR).join(file_name),
+            home_dir.path().join(file_name.replace("_with_creds", "")),
+        )
+        .unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_satori"))
+        .args(["run", "dbt", "--debug", "--dry-run", "--target", "dev"])
+        .arg("--profile-dir")
+        .arg(home_dir.path())
+        .current_dir(home_dir.path())
+        .env("HOME", home_dir.path())
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stdout}{stderr}");
+    assert!(stdout.contains("executing dbt"), "{stdout}");
+    for password in [credentials.password.expose(), "AlicePassword"] {
+        assert!(!stdout.contains(password), "{stdout}");
+        assert!(!stderr.contains(password), "{stderr}");
+    }
+}
+
+/// Runs the satori binary in a subdirectory of a dbt project whose profile, target and host
+/// are read from environment variables.
+#[test]
+fn test_dbt_env_driven_project_from_subdirectory() {
+    let home_dir = temp_dir::generate();
+    let satori_dir = home_dir.path().join(".satori");
+    fs::create_dir(&satori_dir).unwrap();
+    let models_dir = home_dir.path().join("models");
+    fs::create_dir(&models_dir).unwrap();
+    fs::write(
+        satori_dir.join("credentials.json"),
+        serde_json::to_string(&get_old_credentials_expire_two_hours()).unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        satori_dir.join("datastores.json"),
+        serde_json::to_string(&get_mock_datastores("postgres_datastores.json")).unwrap(),
+    )
+    .unwrap();
+    for (file_name, target_name) in [
+        ("dbt_project_env_var.yml", "dbt_project.yml"),
+        ("profiles_env_var.yml", "profiles.yml"),
+    ] {
+        fs::copy(
+            Path::new(DBT_DIR).join(file_name),
+            home_dir.path().join(target_name),
+        )
+        .unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_satori"))
+        .args(["run", "dbt", "--dry-run", "--plan-format", "json"])
+        .current_dir(&models_dir)
+        .env("HOME", home_dir.path())
+        .env("DBT_PROFILES_DIR", home_dir.path())
+        .env_remove("DBT_PROJECT_DIR")
+        .env_remove("DBT_TARGET")
+        .env("SATORI_TEST_DBT_PROFILE", "satori_cli_test_profile")
+        .env("SATORI_TEST_DBT_TARGET", "dev")
+        .env("SATORI_TEST_DBT_HOST", "postgres.example.com")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stdout}{stderr}");
+    let plan: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let args = &plan["commands"][0]["args"];
+    assert_eq!(args[2], "--target", "{stdout}");
+    assert_eq!(args[3], "dev", "{stdout}");
+    let diff = plan["files"][0]["diff"].as_str().unwrap();
+    assert!(diff.contains("+      host: postgres.example.com"), "{diff}");
+    assert!(
+        diff.contains(
+            "+      port: '{{ env_var(''SATORI_TEST_DBT_PORT'', ''5432'') | as_number }}'"
+        ),
+        "{diff}"
+    );
+}
+
+/// `dbt init` appends a new profile, the rest of the file is kept as is.
+#[tokio::test]
+async fn test_dbt_init_new_profile() {
+    let temp_dir = write_init_temp_dir();
+    let original = fs::read_to_string(Path::new(DBT_DIR).join("profiles.yml")).unwrap();
+    fs::write(temp_dir.path().join("profiles.yml"), &original).unwrap();
+
+    let params = build_dbt_init(
+        DbtInitBuilder::default()
+            .datastore_name("redshift".to_string())
+            .database("redshift_db".to_string())
+            .schema("analytics".to_string())
+            .target("dev".to_string())
+            .profile_name(Some("satori_analytics".to_string())),
+        &temp_dir,
+    );
+    tools::dbt::run(params, &b""[..]).await.unwrap();
+
+    let expected = original
+        + &fs::read_to_string(Path::new(DBT_DIR).join("expected_init_new_profile.yml")).unwrap();
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("profiles.yml")).unwrap(),
+        expected
+    );
+}
+
+/// `dbt init` adds the target to the profile of the project, the rest of the file doesn't change.
+#[tokio::test]
+async fn test_dbt_init_existing_profile() {
+    let temp_dir = write_init_temp_dir();
+    fs::copy(
+        Path::new(DBT_DIR).join("profiles_with_comments.yml"),
+        temp_dir.path().join("profiles.yml"),
+    )
+    .unwrap();
+
+    let params = build_dbt_init(
+        DbtInitBuilder::default()
+            .datastore_name("postgres".to_string())
+            .database("customers".to_string())
+            .schema("analytics".to_string())
+            .target("satori".to_string())
+            .project_dir(Some(PathBuf::from(DBT_DIR))),
+        &temp_dir,
+    );
+    tools::dbt::run(params, &b""[..]).await.unwrap();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("profiles.yml")).unwrap(),
+        fs::read_to_string(Path::new(DBT_DIR).join("expected_init_existing_profile.yml")).unwrap()
+    );
+}
+
+/// `dbt init --force` replaces only the lines of the existing target.
+#[tokio::test]
+async fn test_dbt_init_replaces_target() {
+    let temp_dir = write_init_temp_dir();
+    fs::copy(
+        Path::new(DBT_DIR).join("profiles_with_comments.yml"),
+        temp_dir.path().join("profiles.yml"),
+    )
+    .unwrap();
+
+    let params = build_dbt_init(
+        DbtInitBuilder::default()
+            .datastore_name("postgres".to_string())
+            .database("customers".to_string())
+            .schema("analytics".to_string())
+            .target("ci".to_string())
+            .force(true)
+            .project_dir(Some(PathBuf::from(DBT_DIR))),
+        &temp_dir,
+    );
+    tools::dbt::run(params, &b""[..]).await.unwrap();
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("profiles.yml")).unwrap(),
+        fs::read_to_string(Path::new(DBT_DIR).join("expected_init_replaced_target.yml")).unwrap()
+    );
+}
+
+/// Replacing a target which other targets reference would change them, so the file isn't edited.
+#[tokio::test]
+async fn test_dbt_init_referenced_target() {
+    let temp_dir = write_init_temp_dir();
+    let original =
+        fs::read_to_string(Path::new(DBT_DIR).join("profiles_with_comments.yml")).unwrap();
+    fs::write(temp_dir.path().join("profiles.yml"), &original).unwrap();
+
+    let params = build_dbt_init(
+        DbtInitBuilder::default()
+            .datastore_name("postgres".to_string())
+            .database("customers".to_string())
+            .schema("analytics".to_string())
+            .target("dev".to_string())
+            .force(true)
+            .project_dir(Some(PathBuf::from(DBT_DIR))),
+        &temp_dir,
+    );
+    let err = tools::dbt::run(params, &b""[..]).await.unwrap_err();
+
+    assert!(
+        matches!(err, ToolsError::DbtProfileNotEditable { .. }),
+        "{err}"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("profiles.yml")).unwrap(),
+        original
+    );
+}
+
+/// `dbt init` doesn't replace an existing target without --force.
+#[tokio::test]
+async fn test_dbt_init_existing_target() {
+    let temp_dir = write_init_temp_dir();
+    let profiles = read_dbt_profiles_file(DBT_DIR, "profiles.yml");
+    write_dbt_temp_dir(&temp_dir, &profiles);
+
+    let params = build_dbt_init(
+        DbtInitBuilder::default()
+            .datastore_name("postgres".to_string())
+            .database("postgres".to_string())
+            .schema("dev_schema".to_string())
+            .target("dev".to_string())
+            .profile_name(Some("satori_cli_test_profile".to_string())),
+        &temp_dir,
+    );
+    let err = tools::dbt::run(params, &b""[..]).await.unwrap_err();
+
+    assert!(matches!(err, ToolsError::DbtTargetExists { .. }), "{err}");
+    assert_eq!(read_actual_dbt_file(&temp_dir), profiles);
+}
+
+fn write_init_temp_dir() -> TempDir {
+    let temp_dir = temp_dir::generate();
+    write_credentials_temp_dir(&get_old_credentials_expire_two_hours(), &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores("postgres_datastores.json"), &temp_dir);
+    temp_dir
+}
+
+fn build_dbt_init(builder: DbtInitBuilder, temp_dir: &TempDir) -> DbtInit {
+    builder
+        .login(build_login(
+            LoginBuilder::default(),
+            "http://localhost",
+            temp_dir,
+        ))
+        .profiles_path(temp_dir.path().join("profiles.yml"))
+        .build()
+        .unwrap()
+}
+
 async fn run_dbt_with_server_no_asserts<'b>(
     server: &'b MockServer,
     temp_dir: &TempDir,
@@ -237,25 +698,39 @@ fn write_dbt_temp_dir(temp_dir: &TempDir, content: &dbt::Profiles) {
     serde_json::to_writer_pretty(writer, content).unwrap()
 }
 
-fn get_dbt_builder(target: String, profile_name: String) -> DbtBuilder {
+/// The profile is read from the dbt project in [`DBT_DIR`].
+fn get_dbt_builder(target: String) -> DbtBuilder {
     DbtBuilder::default()
         .target(Some(target))
-        .profile_name(profile_name)
+        .project_dir(Some(PathBuf::from(DBT_DIR)))
         .additional_args(vec![])
 }
 
-fn build_mock_executer(temp_dir: &TempDir, credentials: &Credentials) -> MockCommandExecuter {
+/// dbt gets a generated profiles dir, holding only the selected target.
+fn build_mock_executer(
+    credentials: &Credentials,
+    expected_profiles_file: &str,
+) -> MockCommandExecuter {
     let mut command_executer = MockCommandExecuter::new("dbt".to_owned());
     command_executer.expected_args = vec![
+        "--project-dir".to_owned(),
+        DBT_DIR.to_owned(),
         "--profiles-dir".to_owned(),
-        temp_dir.path().to_string_lossy().to_string(),
+        fs::read_to_string(Path::new(DBT_DIR).join(expected_profiles_file)).unwrap(),
         "--target".to_owned(),
         "dev".to_owned(),
     ];
+    command_executer.dir_args = vec![(3, "profiles.yml")];
     command_executer.expected_envs = vec![
         ("PGCHANNELBINDING".to_owned(), "disable".to_owned()),
-        ("SATORI_USERNAME".to_owned(), credentials.username.clone()),
-        ("SATORI_PASSWORD".to_owned(), credentials.password.clone()),
+        (
+            "DBT_ENV_SECRET_SATORI_USERNAME".to_owned(),
+            credentials.username.clone(),
+        ),
+        (
+            "DBT_ENV_SECRET_SATORI_PASSWORD".to_owned(),
+            credentials.password.expose().to_string(),
+        ),
     ];
     command_executer
 }
@@ -264,9 +739,3 @@ fn assert_no_backup(temp_dir: &TempDir) {
     let backup_path = temp_dir.path().join("profiles.bk");
     assert!(fs::metadata(backup_path).is_err())
 }
-
-fn assert_backup(expected: &Profiles, temp_dir: &TempDir) {
-    let backup_file =
-        read_dbt_profiles_file(temp_dir.path().as_os_str().to_str().unwrap(), "profiles.bk");
-    assert_eq!(expected, &backup_file)
-}
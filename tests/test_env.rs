@@ -0,0 +1,168 @@
+#![warn(clippy::all)]
+
+mod test_utils;
+
+use std::path::Path;
+
+use httpmock::MockServer;
+use satori_cli::{
+    env::{self, Env, EnvShell},
+    login::LoginBuilder,
+};
+use tempfile::TempDir;
+use test_utils::{
+    constants::{ACCESS_TOKEN, CODE_CHALLENGE, SATORI_ACCOUNT_ID, SATORI_USER_ID},
+    credentials::{
+        get_new_credentials_expire_two_hours, get_old_credentials_expire_two_hours,
+        get_old_expired_credentials, write_credentials_temp_dir,
+    },
+    datastores::{get_mock_datastores, write_datastores_temp_dir},
+    login_helpers::build_login,
+    mock_server::{
+        get_access_details_db_empty_response_path, get_encoded_challenge, run_server_no_asserts,
+        DatabaseCredentialsMock, DatastoresMock, ServerJwtMock, UserInfoMock,
+    },
+    temp_dir,
+};
+
+#[tokio::test]
+async fn test_env_postgres_bash() {
+    let temp_dir = temp_dir::generate();
+    write_credentials_temp_dir(&get_old_credentials_expire_two_hours(), &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores("psql_datastores.json"), &temp_dir);
+
+    let server = MockServer::start();
+    let (output, (server_jwt_mock, user_info_mock, database_credentials_mock, datastores_mock)) =
+        run_env_with_server(
+            &server,
+            &temp_dir,
+            &get_access_details_db_empty_response_path(),
+            "postgres",
+            Some("customers"),
+            EnvShell::Bash,
+            false,
+        )
+        .await;
+
+    server_jwt_mock.assert_hits(0);
+    user_info_mock.assert_hits(0);
+    database_credentials_mock.assert_hits(0);
+    datastores_mock.assert_hits(0);
+    assert_eq!(
+        output,
+        "export PGHOST='postgres.example.com'\n\
+         export PGPORT='5432'\n\
+         export PGUSER='OLD_USER'\n\
+         export PGPASSWORD='OLD_PASSWORD'\n\
+         export PGDATABASE='customers'\n\
+         export PGCHANNELBINDING='disable'\n"
+    );
+}
+
+/// Expired credentials are refreshed before the variables are printed
+#[tokio::test]
+async fn test_env_s3_credentials_from_server() {
+    let temp_dir = temp_dir::generate();
+    write_credentials_temp_dir(&get_old_expired_credentials(), &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores("s3_datastores.json"), &temp_dir);
+    let new_credentials = get_new_credentials_expire_two_hours();
+
+    let server = MockServer::start();
+    let (output, (server_jwt_mock, user_info_mock, database_credentials_mock, datastores_mock)) =
+        run_env_with_server(
+            &server,
+            &temp_dir,
+            &get_access_details_db_empty_response_path(),
+            "s3_datastore",
+            None,
+            EnvShell::Fish,
+            false,
+        )
+        .await;
+
+    server_jwt_mock.assert_hits(1);
+    user_info_mock.assert_hits(1);
+    database_credentials_mock.assert_hits(1);
+    datastores_mock.assert_hits(0);
+    assert_eq!(
+        output,
+        format!(
+            "set -gx AWS_ACCESS_KEY_ID '{}'\n\
+             set -gx AWS_SECRET_ACCESS_KEY '{}'\n\
+             set -gx AWS_ENDPOINT_URL 'https://s3.example.com'\n",
+            new_credentials.username,
+            new_credentials.password.expose()
+        )
+    );
+}
+
+/// Unsetting the variables doesn't require credentials
+#[tokio::test]
+async fn test_env_unset_without_credentials() {
+    let temp_dir = temp_dir::generate();
+    write_datastores_temp_dir(&get_mock_datastores("mongo_datastores.json"), &temp_dir);
+
+    let server = MockServer::start();
+    let (output, (server_jwt_mock, user_info_mock, database_credentials_mock, datastores_mock)) =
+        run_env_with_server(
+            &server,
+            &temp_dir,
+            &get_access_details_db_empty_response_path(),
+            "mongo_datastore",
+            None,
+            EnvShell::PowerShell,
+            true,
+        )
+        .await;
+
+    server_jwt_mock.assert_hits(0);
+    user_info_mock.assert_hits(0);
+    database_credentials_mock.assert_hits(0);
+    datastores_mock.assert_hits(0);
+    assert_eq!(
+        output,
+        "Remove-Item Env:MONGODB_URI -ErrorAction SilentlyContinue\n"
+    );
+}
+
+async fn run_env_with_server<'b>(
+    server: &'b MockServer,
+    temp_dir: &TempDir,
+    datastores_info_file_path: &Path,
+    datastore_name: &str,
+    database: Option<&str>,
+    shell: EnvShell,
+    unset: bool,
+) -> (
+    String,
+    (
+        ServerJwtMock<'b>,
+        UserInfoMock<'b>,
+        DatabaseCredentialsMock<'b>,
+        DatastoresMock<'b>,
+    ),
+) {
+    let params = Env {
+        login: build_login(LoginBuilder::default(), &server.base_url(), temp_dir),
+        datastore_name: datastore_name.to_string(),
+        datastore_id: None,
+        database: database.map(str::to_string),
+        shell,
+        unset,
+    };
+    let mocks = run_server_no_asserts(
+        server,
+        datastores_info_file_path,
+        CODE_CHALLENGE,
+        ACCESS_TOKEN.to_string(),
+        SATORI_USER_ID.to_string(),
+        SATORI_ACCOUNT_ID.to_string(),
+    )
+    .await;
+    let encoded_challenge = get_encoded_challenge();
+    let mut output = Vec::new();
+    env::run(params, &encoded_challenge[..], &mut output)
+        .await
+        .unwrap();
+    (String::from_utf8(output).unwrap(), mocks)
+}
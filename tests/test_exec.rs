@@ -0,0 +1,121 @@
+#![warn(clippy::all)]
+
+mod test_utils;
+
+use httpmock::MockServer;
+use satori_cli::{
+    login::LoginBuilder,
+    run::{exec, CommandStatus, ExecBuilder},
+};
+use test_utils::{
+    constants::{ACCESS_TOKEN, CODE_CHALLENGE, SATORI_ACCOUNT_ID, SATORI_USER_ID},
+    credentials::{get_old_credentials_expire_two_hours, write_credentials_temp_dir},
+    datastores::{get_mock_datastores, write_datastores_temp_dir},
+    login_helpers::build_login,
+    mock_command_executer::MockCommandExecuter,
+    mock_server::{
+        get_access_details_db_empty_response_path, get_encoded_challenge, run_server_no_asserts,
+    },
+    temp_dir,
+};
+
+/// Any command gets the Postgres environment variables, the arguments are passed as is
+#[tokio::test]
+async fn test_exec_postgres() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores("psql_datastores.json"), &temp_dir);
+
+    let mut mock_executer = MockCommandExecuter::new("python".to_string());
+    mock_executer.expected_args = vec!["script.py".to_string(), "--verbose".to_string()];
+    mock_executer.expected_envs = vec![
+        ("PGHOST".to_string(), "postgres.example.com".to_string()),
+        ("PGPORT".to_string(), "5432".to_string()),
+        ("PGUSER".to_string(), credentials.username.clone()),
+        (
+            "PGPASSWORD".to_string(),
+            credentials.password.expose().to_string(),
+        ),
+        ("PGDATABASE".to_string(), "customers".to_string()),
+        ("PGCHANNELBINDING".to_string(), "disable".to_string()),
+    ];
+
+    let server = MockServer::start();
+    let (server_jwt_mock, user_info_mock, database_credentials_mock, datastores_mock) =
+        run_server_no_asserts(
+            &server,
+            &get_access_details_db_empty_response_path(),
+            CODE_CHALLENGE,
+            ACCESS_TOKEN.to_string(),
+            SATORI_USER_ID.to_string(),
+            SATORI_ACCOUNT_ID.to_string(),
+        )
+        .await;
+    let params = ExecBuilder::default()
+        .login(build_login(
+            LoginBuilder::default(),
+            &server.base_url(),
+            &temp_dir,
+        ))
+        .datastore_name("postgres".to_string())
+        .database(Some("customers".to_string()))
+        .command("python".to_string())
+        .args(vec!["script.py".to_string(), "--verbose".to_string()])
+        .build()
+        .unwrap();
+    let encoded_challenge = get_encoded_challenge();
+    let status = exec::run(params, &encoded_challenge[..], mock_executer)
+        .await
+        .unwrap();
+    assert_eq!(status, CommandStatus::SUCCESS);
+
+    server_jwt_mock.assert_hits(0);
+    user_info_mock.assert_hits(0);
+    database_credentials_mock.assert_hits(0);
+    datastores_mock.assert_hits(0);
+    MockCommandExecuter::assert();
+}
+
+/// The exit status of the command is returned, so satori exits with it
+#[tokio::test]
+async fn test_exec_command_failed() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&get_mock_datastores("psql_datastores.json"), &temp_dir);
+
+    let mut mock_executer = MockCommandExecuter::new("psql".to_string());
+    mock_executer.expected_args = vec!["-c".to_string(), "bad sql".to_string()];
+    mock_executer.expected_envs = vec![
+        ("PGHOST".to_string(), "postgres.example.com".to_string()),
+        ("PGPORT".to_string(), "5432".to_string()),
+        ("PGUSER".to_string(), credentials.username.clone()),
+        (
+            "PGPASSWORD".to_string(),
+            credentials.password.expose().to_string(),
+        ),
+        ("PGCHANNELBINDING".to_string(), "disable".to_string()),
+    ];
+    mock_executer.status = CommandStatus::Exited(3);
+
+    let server = MockServer::start();
+    let params = ExecBuilder::default()
+        .login(build_login(
+            LoginBuilder::default(),
+            &server.base_url(),
+            &temp_dir,
+        ))
+        .datastore_name("postgres".to_string())
+        .command("psql".to_string())
+        .args(vec!["-c".to_string(), "bad sql".to_string()])
+        .build()
+        .unwrap();
+    let encoded_challenge = get_encoded_challenge();
+    let status = exec::run(params, &encoded_challenge[..], mock_executer)
+        .await
+        .unwrap();
+
+    assert_eq!(status.exit_code(), 3);
+    MockCommandExecuter::assert();
+}
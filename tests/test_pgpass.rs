@@ -9,8 +9,12 @@ use httpmock::MockServer;
 use satori_cli::tools::pgpass::data::PgPassBuilder;
 use satori_cli::tools::pgpass::{self, PgPass};
 use satori_cli::{
+    helpers::{
+        dry_run::{DryRun, PlanFormat},
+        satori_console::DatastoreType,
+    },
     login::{Login, LoginBuilder},
-    tools::pgpass::flow::PgPassEntry,
+    tools::pgpass::file::{PgPassEntry, PgPassFile},
 };
 use tempfile::TempDir;
 use test_utils::{
@@ -80,7 +84,7 @@ async fn test_pgpass_credentials_from_server() {
     )
     .await;
 
-    let mut expected_pgpass = read_pgpass_file(PGPASS_DIR, "expected_pgpass_satori_only");
+    let mut expected_pgpass = read_pgpass_file(PGPASS_DIR, "expected_pgpass_new_creds");
     let mut actual_pgpass = read_actual_pgpass_file(&temp_dir);
 
     validates_pgpass(&mut expected_pgpass, &mut actual_pgpass);
@@ -111,13 +115,15 @@ async fn test_pgpass_non_satori_entries() {
     validates_pgpass(&mut expected_pgpass, &mut actual_pgpass);
 }
 
-/// User already have satori pgpass entries, validates we update the credentials.
+/// User already have satori pgpass entries, written by an earlier version without the satori
+/// block, validates they are moved to the block with the new credentials.
 #[tokio::test]
 async fn test_pgpass_expired_credentials() {
     let temp_dir = temp_dir::generate();
     let datastores_info = get_mock_datastores("postgres_datastores.json");
     let datastores_entries_response_path = get_access_details_db_empty_response_path();
-    let old_pgpass = read_pgpass_file(PGPASS_DIR, "expected_pgpass_satori_only");
+    let mut old_pgpass = read_pgpass_file(PGPASS_DIR, "non_satori_entries_pgpass");
+    old_pgpass.extend(read_pgpass_file(PGPASS_DIR, "expired_pgpass_satori_only"));
 
     write_datastores_temp_dir(&datastores_info, &temp_dir);
     write_pgpass_temp_dir(&temp_dir, old_pgpass);
@@ -130,11 +136,271 @@ async fn test_pgpass_expired_credentials() {
     .await;
 
     let mut expected_pgpass = read_pgpass_file(PGPASS_DIR, "expected_pgpass_new_creds");
+    expected_pgpass.extend(read_pgpass_file(PGPASS_DIR, "non_satori_entries_pgpass"));
     let mut actual_pgpass = read_actual_pgpass_file(&temp_dir);
 
     validates_pgpass(&mut expected_pgpass, &mut actual_pgpass);
 }
 
+/// The satori entries are added in a block at the top of the file, the other lines, comments,
+/// wildcards, escapes, invalid lines and the entry of another user of a satori host, are kept as
+/// is.
+#[tokio::test]
+async fn test_pgpass_keeps_other_lines() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    let old_pgpass = fs::read_to_string(Path::new(PGPASS_DIR).join("user_lines_pgpass")).unwrap();
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    fs::write(temp_dir.path().join(".pgpass"), &old_pgpass).unwrap();
+
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        PgPassBuilder::default(),
+    )
+    .await;
+
+    let password = credentials.password.expose();
+    let expected = format!(
+        "# BEGIN satori entries, regenerated by `satori pgpass`\n\
+         postgres.example.com:5432:customers:OLD_USER:{password}\n\
+         postgres.example.com:5432:postgres:OLD_USER:{password}\n\
+         redshift.example.com:5432:customers:OLD_USER:{password}\n\
+         redshift.example.com:5432:redshift_db:OLD_USER:{password}\n\
+         # END satori entries\n\
+         {old_pgpass}"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".pgpass")).unwrap(),
+        expected
+    );
+}
+
+/// Without a satori block, the entries of the satori user on satori hosts are moved to the block,
+/// and the entries of other users on the same hosts stay where they are.
+#[tokio::test]
+async fn test_pgpass_keeps_entries_of_other_users() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    fs::write(
+        temp_dir.path().join(".pgpass"),
+        "postgres.example.com:5432:postgres:SERVICE_ACCOUNT:SERVICE_PASSWORD\n\
+         postgres.example.com:5432:postgres:OLD_USER:EXPIRED_PASSWORD\n",
+    )
+    .unwrap();
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        PgPassBuilder::default(),
+    )
+    .await;
+
+    let password = credentials.password.expose();
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".pgpass")).unwrap(),
+        format!(
+            "# BEGIN satori entries, regenerated by `satori pgpass`\n\
+             postgres.example.com:5432:customers:OLD_USER:{password}\n\
+             postgres.example.com:5432:postgres:OLD_USER:{password}\n\
+             redshift.example.com:5432:customers:OLD_USER:{password}\n\
+             redshift.example.com:5432:redshift_db:OLD_USER:{password}\n\
+             # END satori entries\n\
+             postgres.example.com:5432:postgres:SERVICE_ACCOUNT:SERVICE_PASSWORD\n"
+        )
+    );
+}
+
+/// The satori block is regenerated, entries of datastores which are gone are removed.
+#[tokio::test]
+async fn test_pgpass_prunes_stale_entries() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    fs::copy(
+        Path::new(PGPASS_DIR).join("satori_block_pgpass"),
+        temp_dir.path().join(".pgpass"),
+    )
+    .unwrap();
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        PgPassBuilder::default(),
+    )
+    .await;
+
+    let mut expected_pgpass =
+        read_pgpass_file(PGPASS_DIR, "expected_with_non_satori_entries_pgpass");
+    let mut actual_pgpass = read_actual_pgpass_file(&temp_dir);
+    validates_pgpass(&mut expected_pgpass, &mut actual_pgpass);
+}
+
+/// A dry run lists the entries which would be written, without touching the pgpass file.
+#[tokio::test]
+async fn test_pgpass_dry_run() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("postgres_datastores.json");
+    let datastores_entries_response_path = get_access_details_db_empty_response_path();
+    fs::copy(
+        Path::new(PGPASS_DIR).join("satori_block_pgpass"),
+        temp_dir.path().join(".pgpass"),
+    )
+    .unwrap();
+
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    let pgpass_before = fs::read_to_string(temp_dir.path().join(".pgpass")).unwrap();
+
+    let dry_run = DryRun::new(PlanFormat::Json);
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &datastores_entries_response_path,
+        PgPassBuilder::default().dry_run(Some(dry_run.clone())),
+    )
+    .await;
+
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".pgpass")).unwrap(),
+        pgpass_before
+    );
+    let plan = dry_run.plan();
+    assert_eq!(plan.files.len(), 1);
+    let username = credentials.username;
+    assert_eq!(
+        plan.files[0].added,
+        vec![
+            format!("redshift.example.com:5432:customers:{username}"),
+            format!("redshift.example.com:5432:redshift_db:{username}"),
+        ]
+    );
+    assert_eq!(
+        plan.files[0].updated,
+        vec![
+            format!("postgres.example.com:5432:customers:{username}"),
+            format!("postgres.example.com:5432:postgres:{username}"),
+        ]
+    );
+    assert_eq!(
+        plan.files[0].removed,
+        vec!["stale.example.com:5432:postgres:OLD_USER"]
+    );
+}
+
+/// Validates that only the datastores matching the name patterns are added, and that the
+/// default port of the type is used when the datastore has no port
+#[tokio::test]
+async fn test_pgpass_datastore_filter() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("filtered_pgpass_datastores.json");
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &get_access_details_db_empty_response_path(),
+        PgPassBuilder::default().datastores(vec!["prod-*".to_string()]),
+    )
+    .await;
+
+    let actual_pgpass = read_actual_pgpass_file(&temp_dir)
+        .iter()
+        .map(PgPassEntry::without_password)
+        .collect::<Vec<String>>();
+    assert_eq!(
+        actual_pgpass,
+        vec![
+            "prod-postgres.example.com:5432:customers:OLD_USER",
+            "prod-postgres.example.com:5432:postgres:OLD_USER",
+            "prod-redshift.example.com:5439:dev:OLD_USER",
+        ]
+    );
+}
+
+/// Validates that the filters select the datastores which are refreshed, the entries of the
+/// other datastores are kept, and only the entries of datastores which are gone are removed
+#[tokio::test]
+async fn test_pgpass_filter_keeps_other_datastores() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("filtered_pgpass_datastores.json");
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+    fs::copy(
+        Path::new(PGPASS_DIR).join("filtered_block_pgpass"),
+        temp_dir.path().join(".pgpass"),
+    )
+    .unwrap();
+
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &get_access_details_db_empty_response_path(),
+        PgPassBuilder::default().datastores(vec!["prod-*".to_string()]),
+    )
+    .await;
+
+    let password = credentials.password.expose();
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".pgpass")).unwrap(),
+        format!(
+            "# BEGIN satori entries, regenerated by `satori pgpass`\n\
+             prod-postgres.example.com:5432:customers:OLD_USER:{password}\n\
+             prod-postgres.example.com:5432:postgres:OLD_USER:{password}\n\
+             prod-redshift.example.com:5439:dev:OLD_USER:{password}\n\
+             staging-postgres.example.com:5432:postgres:OLD_USER:EXPIRED_PASSWORD\n\
+             # END satori entries\n"
+        )
+    );
+}
+
+/// Validates the wildcard entries of the datastores of the selected types, including the
+/// datastores without databases
+#[tokio::test]
+async fn test_pgpass_wildcard_databases() {
+    let temp_dir = temp_dir::generate();
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("filtered_pgpass_datastores.json");
+    write_credentials_temp_dir(&credentials, &temp_dir);
+    write_datastores_temp_dir(&datastores_info, &temp_dir);
+
+    run_pgpass_with_server_assert_no_calls_to_server(
+        &temp_dir,
+        &get_access_details_db_empty_response_path(),
+        PgPassBuilder::default()
+            .types(vec![DatastoreType::Postgresql, DatastoreType::Greenplum])
+            .wildcard_databases(true),
+    )
+    .await;
+
+    let actual_pgpass = read_actual_pgpass_file(&temp_dir)
+        .iter()
+        .map(PgPassEntry::without_password)
+        .collect::<Vec<String>>();
+    assert_eq!(
+        actual_pgpass,
+        vec![
+            "prod-greenplum.example.com:5432:*:OLD_USER",
+            "prod-postgres.example.com:5432:*:OLD_USER",
+            "staging-postgres.example.com:5432:*:OLD_USER",
+        ]
+    );
+}
+
 async fn run_pgpass_with_server_assert_no_calls_to_server(
     temp_dir: &TempDir,
     datastores_info_file_path: &Path,
@@ -224,10 +490,9 @@ fn read_actual_pgpass_file(temp_dir: &TempDir) -> Vec<PgPassEntry> {
 pub fn read_pgpass_file(file_path: &str, filename: &str) -> Vec<PgPassEntry> {
     let file_path = Path::new(file_path).join(filename);
     let pgpass_file = fs::read_to_string(file_path).unwrap();
-    pgpass_file
-        .lines()
-        .map(|line| PgPassEntry::from(line.to_string()))
-        .collect()
+    let (pgpass, invalid_lines) = PgPassFile::parse(&pgpass_file);
+    assert!(invalid_lines.is_empty(), "{invalid_lines:?}");
+    pgpass.entries().cloned().collect()
 }
 
 fn validates_pgpass(expected_pgpass: &mut Vec<PgPassEntry>, actual_pgpass: &mut Vec<PgPassEntry>) {
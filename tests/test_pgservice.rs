@@ -0,0 +1,135 @@
+#![warn(clippy::all)]
+
+use std::fs;
+
+use httpmock::MockServer;
+use satori_cli::{
+    helpers::dry_run::{DryRun, PlanFormat},
+    login::LoginBuilder,
+    tools::pgservice::{self, data::PgServiceBuilder},
+};
+use tempfile::TempDir;
+use test_utils::{
+    constants::{ACCESS_TOKEN, CODE_CHALLENGE, SATORI_ACCOUNT_ID, SATORI_USER_ID},
+    login_helpers::build_login,
+    mock_server::{get_encoded_challenge, run_server_no_asserts},
+};
+
+use crate::test_utils::{
+    credentials::{get_old_credentials_expire_two_hours, write_credentials_temp_dir},
+    datastores::{get_mock_datastores, write_datastores_temp_dir},
+    mock_server::get_access_details_db_empty_response_path,
+    temp_dir,
+};
+
+mod test_utils;
+
+const PGSERVICE_DIR: &str = "tests/pgservice_files";
+const SERVICE_FILE_NAME: &str = ".pg_service.conf";
+
+/// Validates that a service is added for every database of the postgres dialect datastores, with
+/// the default port of the type when the datastore has no port
+#[tokio::test]
+async fn test_pgservice_new_file() {
+    let temp_dir = temp_dir::generate();
+
+    run_pgservice(&temp_dir, PgServiceBuilder::default()).await;
+
+    assert_eq!(
+        read_actual_service_file(&temp_dir),
+        fs::read_to_string(format!("{PGSERVICE_DIR}/expected_new_services.conf")).unwrap()
+    );
+}
+
+/// Validates that the services of the selected datastores are regenerated, the ones of datastores
+/// which are gone removed, the ones of the other datastores kept, and the lines outside of the
+/// satori block kept as they are
+#[tokio::test]
+async fn test_pgservice_existing_file() {
+    let temp_dir = temp_dir::generate();
+    fs::copy(
+        format!("{PGSERVICE_DIR}/user_services.conf"),
+        temp_dir.path().join(SERVICE_FILE_NAME),
+    )
+    .unwrap();
+
+    run_pgservice(
+        &temp_dir,
+        PgServiceBuilder::default().datastores(vec!["prod-postgres".to_string()]),
+    )
+    .await;
+
+    assert_eq!(
+        read_actual_service_file(&temp_dir),
+        fs::read_to_string(format!("{PGSERVICE_DIR}/expected_existing_services.conf")).unwrap()
+    );
+}
+
+/// Validates that a dry run records the services without writing the file
+#[tokio::test]
+async fn test_pgservice_dry_run() {
+    let temp_dir = temp_dir::generate();
+    fs::copy(
+        format!("{PGSERVICE_DIR}/user_services.conf"),
+        temp_dir.path().join(SERVICE_FILE_NAME),
+    )
+    .unwrap();
+    let dry_run = DryRun::new(PlanFormat::Text);
+
+    run_pgservice(
+        &temp_dir,
+        PgServiceBuilder::default()
+            .datastores(vec!["prod-*".to_string()])
+            .dry_run(Some(dry_run.clone())),
+    )
+    .await;
+
+    assert_eq!(
+        read_actual_service_file(&temp_dir),
+        fs::read_to_string(format!("{PGSERVICE_DIR}/user_services.conf")).unwrap()
+    );
+    let plan = dry_run.plan();
+    assert_eq!(
+        plan.files[0].added,
+        vec!["satori_prod_postgres_customers", "satori_prod_redshift_dev"]
+    );
+    assert_eq!(plan.files[0].updated, vec!["satori_prod_postgres_postgres"]);
+    assert_eq!(
+        plan.files[0].removed,
+        vec!["satori_removed_datastore_postgres"]
+    );
+}
+
+async fn run_pgservice(temp_dir: &TempDir, pgservice_builder: PgServiceBuilder) {
+    let credentials = get_old_credentials_expire_two_hours();
+    let datastores_info = get_mock_datastores("filtered_pgpass_datastores.json");
+    write_credentials_temp_dir(&credentials, temp_dir);
+    write_datastores_temp_dir(&datastores_info, temp_dir);
+
+    let server = MockServer::start();
+    let login = build_login(LoginBuilder::default(), &server.base_url(), temp_dir);
+    let pgservice_params = pgservice_builder
+        .login(login)
+        .path(temp_dir.path().join(SERVICE_FILE_NAME))
+        .build()
+        .unwrap();
+    let (server_jwt_mock, ..) = run_server_no_asserts(
+        &server,
+        &get_access_details_db_empty_response_path(),
+        CODE_CHALLENGE,
+        ACCESS_TOKEN.to_string(),
+        SATORI_USER_ID.to_string(),
+        SATORI_ACCOUNT_ID.to_string(),
+    )
+    .await;
+    let encoded_challenge = get_encoded_challenge();
+    pgservice::run(pgservice_params, &encoded_challenge[..])
+        .await
+        .unwrap();
+
+    server_jwt_mock.assert_hits(0);
+}
+
+fn read_actual_service_file(temp_dir: &TempDir) -> String {
+    fs::read_to_string(temp_dir.path().join(SERVICE_FILE_NAME)).unwrap()
+}
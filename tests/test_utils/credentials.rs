@@ -20,6 +20,7 @@ pub fn get_old_credentials_expire_two_hours() -> Credentials {
     credentials
 }
 
+#[allow(dead_code)]
 pub fn get_old_expired_credentials() -> Credentials {
     let mut credentials = get_mock_credentials("old_credentials.json");
     credentials.expires_at = get_expire_two_hours_ago();
@@ -1,6 +1,6 @@
 use std::sync::OnceLock;
 
-use satori_cli::run::ExecuteCommand;
+use satori_cli::run::{CommandStatus, ExecuteCommand};
 
 static CALLED: OnceLock<bool> = OnceLock::new();
 
@@ -8,6 +8,13 @@ pub struct MockCommandExecuter {
     pub expected_command: String,
     pub expected_args: Vec<String>,
     pub expected_envs: Vec<(String, String)>,
+    /// Indexes of args which are paths of files, they are compared by their content.
+    pub file_args: Vec<usize>,
+    /// Indexes of args which are directories, they are compared by the content of the named
+    /// file in them.
+    pub dir_args: Vec<(usize, &'static str)>,
+    /// Returned by execute, as if the command ended this way.
+    pub status: CommandStatus,
 }
 impl MockCommandExecuter {
     #[allow(dead_code)]
@@ -16,6 +23,9 @@ impl MockCommandExecuter {
             expected_command: command_name,
             expected_args: vec![],
             expected_envs: vec![],
+            file_args: vec![],
+            dir_args: vec![],
+            status: CommandStatus::SUCCESS,
         }
     }
     #[allow(dead_code)]
@@ -30,7 +40,7 @@ impl ExecuteCommand for MockCommandExecuter {
         command_name: &str,
         args: A,
         env: T,
-    ) -> Result<(), satori_cli::run::errors::RunError>
+    ) -> Result<CommandStatus, satori_cli::run::errors::RunError>
     where
         T: IntoIterator<Item = (S, V)>,
         A: IntoIterator<Item = G>,
@@ -40,10 +50,24 @@ impl ExecuteCommand for MockCommandExecuter {
     {
         assert_eq!(command_name, self.expected_command);
 
-        let string_args = args
+        let mut string_args = args
             .into_iter()
             .map(|arg| arg.as_ref().to_str().unwrap().to_string())
             .collect::<Vec<String>>();
+        for index in &self.file_args {
+            let path = &string_args[*index];
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(path).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600, "{path} is readable by other users");
+            }
+            string_args[*index] = std::fs::read_to_string(path).unwrap();
+        }
+        for (index, file_name) in &self.dir_args {
+            let path = std::path::Path::new(&string_args[*index]).join(file_name);
+            string_args[*index] = std::fs::read_to_string(path).unwrap();
+        }
         assert_eq!(string_args, self.expected_args);
 
         let string_envs = env
@@ -57,6 +81,6 @@ impl ExecuteCommand for MockCommandExecuter {
             .collect::<Vec<(String, String)>>();
         assert_eq!(string_envs, self.expected_envs);
         CALLED.set(true).unwrap();
-        Ok(())
+        Ok(self.status)
     }
 }
@@ -58,7 +58,7 @@ pub fn oauth<'a>(server: &'a MockServer, code_challenge: &str, jwt: String) -> M
             .path("/api/oauth/token")
             .query_param("code", code_challenge);
         then.status(201).json_body_obj(&OauthResponse {
-            access_token: jwt,
+            access_token: jwt.into(),
             token_type: "oauth".to_string(),
             expires_in,
         });